@@ -4,4 +4,6 @@ pub mod s03;
 pub mod s04;
 pub mod s05;
 pub mod s06;
+pub mod s07;
+pub mod s08;
 pub mod util;
@@ -1 +1,501 @@
-fn main() {}
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, Read, Write},
+    process, str,
+};
+
+use cryptopals::{
+    s01::{
+        aes_ecb::{aes128_ecb_decode, aes128_ecb_encode, aes_ecb_detector},
+        key_xor_analyzer::KeyXorAnalyzer,
+    },
+    s02::{
+        aes_cbc::{aes128_cbc_decode, aes128_cbc_encode},
+        padding::remove_pkcs7_padding,
+    },
+    s03::aes_ctr::{aes128_ctr_decode, aes128_ctr_encode},
+    s04::{hmac::sha1_hmac, md4::md4, sha1::sha1},
+    s05::rsa::{rsa_keygen, RsaPrivate, RsaPublic},
+    util::{
+        base_64::Base64,
+        integer::{from_bytes, to_bytes},
+    },
+};
+use rug::Integer;
+
+const USAGE: &str = "usage: cryptopals <command> [options]
+
+commands:
+  encrypt --mode ecb|cbc|ctr --key KEY [--iv IV]
+  decrypt --mode ecb|cbc|ctr --key KEY [--iv IV]
+  hash sha1|md4|hmac [--key KEY]
+  break-xor --corpus FILE [--max-keylength N] [--keys-to-try N]
+  detect-ecb
+  rsa keygen [--bits N]
+  rsa encrypt|decrypt --key FILE
+
+options:
+  --in FILE, --out FILE        read from / write to a file instead of stdin / stdout
+  --in-format raw|hex|base64   encoding of the input (default raw)
+  --out-format raw|hex|base64  encoding of the output (default raw, hex for hashes)
+  --key-format hex|base64      encoding of KEY and IV (default hex)
+
+Keys are 16 bytes and CTR takes a nonce of up to 16 bytes as IV. detect-ecb reads one
+ciphertext per line, hex encoded unless --in-format says otherwise.";
+
+type Result<T> = std::result::Result<T, String>;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Raw,
+    Hex,
+    Base64,
+}
+
+impl Format {
+    fn parse(name: &str) -> Result<Format> {
+        match name {
+            "raw" => Ok(Format::Raw),
+            "hex" => Ok(Format::Hex),
+            "base64" => Ok(Format::Base64),
+            _ => Err(format!("unknown format {name}")),
+        }
+    }
+
+    fn decode(&self, input: &[u8]) -> Result<Vec<u8>> {
+        if *self == Format::Raw {
+            return Ok(input.to_vec());
+        }
+        let text: String = str::from_utf8(input)
+            .map_err(|_| "input is not valid text".to_string())?
+            .split_whitespace()
+            .collect();
+        match self {
+            Format::Raw => unreachable!(),
+            Format::Hex => hex::decode(text).map_err(|e| format!("invalid hex: {e}")),
+            Format::Base64 => Base64::new_from_string(&text)
+                .map(|base64| base64.to_bytes().to_vec())
+                .map_err(|e| format!("invalid base64: {e}")),
+        }
+    }
+
+    fn encode(&self, output: &[u8]) -> Vec<u8> {
+        match self {
+            Format::Raw => output.to_vec(),
+            Format::Hex => (hex::encode(output) + "\n").into_bytes(),
+            Format::Base64 => (Base64::new_from_bytes(output).serialize() + "\n").into_bytes(),
+        }
+    }
+}
+
+// Options are all of the form --name value, everything else is positional.
+struct Arguments {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Arguments {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Arguments> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        while let Some(arg) = args.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("missing value for --{name}"))?;
+                options.insert(name.to_string(), value);
+            } else {
+                positional.push(arg);
+            }
+        }
+        Ok(Arguments {
+            positional,
+            options,
+        })
+    }
+
+    fn positional(&self, index: usize, what: &str) -> Result<&str> {
+        self.positional
+            .get(index)
+            .map(|arg| arg.as_str())
+            .ok_or_else(|| format!("missing {what}"))
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|value| value.as_str())
+    }
+
+    fn required(&self, name: &str) -> Result<&str> {
+        self.option(name)
+            .ok_or_else(|| format!("missing option --{name}"))
+    }
+
+    fn number(&self, name: &str, default: usize) -> Result<usize> {
+        match self.option(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--{name} must be a number")),
+            None => Ok(default),
+        }
+    }
+
+    fn format(&self, name: &str, default: Format) -> Result<Format> {
+        self.option(name).map_or(Ok(default), Format::parse)
+    }
+
+    fn key(&self, name: &str) -> Result<Vec<u8>> {
+        let format = self.format("key-format", Format::Hex)?;
+        if format == Format::Raw {
+            return Err("keys cannot be given raw".to_string());
+        }
+        format.decode(self.required(name)?.as_bytes())
+    }
+
+    fn read_input(&self) -> Result<Vec<u8>> {
+        let input = match self.option("in") {
+            Some(path) => fs::read(path).map_err(|e| format!("cannot read {path}: {e}"))?,
+            None => {
+                let mut input = Vec::new();
+                io::stdin()
+                    .read_to_end(&mut input)
+                    .map_err(|e| format!("cannot read stdin: {e}"))?;
+                input
+            }
+        };
+        self.format("in-format", Format::Raw)?.decode(&input)
+    }
+
+    fn write_output(&self, output: &[u8], default: Format) -> Result<()> {
+        let output = self.format("out-format", default)?.encode(output);
+        match self.option("out") {
+            Some(path) => fs::write(path, output).map_err(|e| format!("cannot write {path}: {e}")),
+            None => io::stdout()
+                .write_all(&output)
+                .map_err(|e| format!("cannot write stdout: {e}")),
+        }
+    }
+}
+
+fn cipher(args: &Arguments, encrypt: bool) -> Result<()> {
+    let mode = args.required("mode")?;
+    let key = args.key("key")?;
+    let input = args.read_input()?;
+
+    let output = match (mode, encrypt) {
+        ("ecb", true) => aes128_ecb_encode(&input, &key),
+        ("ecb", false) => {
+            aes128_ecb_decode(&input, &key).and_then(|plain| remove_pkcs7_padding(&plain))
+        }
+        ("cbc", true) => aes128_cbc_encode(&input, &key, &args.key("iv")?),
+        ("cbc", false) => aes128_cbc_decode(&input, &key, &args.key("iv")?)
+            .and_then(|plain| remove_pkcs7_padding(&plain)),
+        ("ctr", true) => aes128_ctr_encode(&input, &key, &args.key("iv")?),
+        ("ctr", false) => aes128_ctr_decode(&input, &key, &args.key("iv")?),
+        _ => return Err(format!("unknown mode {mode}")),
+    }
+    .map_err(|e| e.to_string())?;
+    args.write_output(&output, Format::Raw)
+}
+
+fn hash(args: &Arguments) -> Result<()> {
+    let algorithm = args.positional(1, "hash algorithm")?;
+    let input = args.read_input()?;
+    let output = match algorithm {
+        "sha1" => sha1(&input).to_vec(),
+        "md4" => md4(&input).to_vec(),
+        "hmac" => sha1_hmac(&args.key("key")?, &input).to_vec(),
+        _ => return Err(format!("unknown hash algorithm {algorithm}")),
+    };
+    args.write_output(&output, Format::Hex)
+}
+
+fn break_xor(args: &Arguments) -> Result<()> {
+    let corpus_path = args.required("corpus")?;
+    let corpus =
+        fs::read_to_string(corpus_path).map_err(|e| format!("cannot read {corpus_path}: {e}"))?;
+    let max_keylength = args.number("max-keylength", 40)?;
+    let keys_to_try = args.number("keys-to-try", 10)?;
+    let input = args.read_input()?;
+
+    // the key length guess compares the first four blocks of each length
+    if input.len() < 4 * max_keylength {
+        return Err(format!(
+            "ciphertext must be at least {} bytes long",
+            4 * max_keylength
+        ));
+    }
+    if keys_to_try == 0 || keys_to_try >= max_keylength {
+        return Err("--keys-to-try must be between 1 and --max-keylength".to_string());
+    }
+
+    let analyzer = KeyXorAnalyzer::new(corpus.replace('\n', "").as_bytes());
+    let (plain, _, key) = analyzer.analyze(&input, max_keylength, keys_to_try);
+    eprintln!("key: {}", String::from_utf8_lossy(&key));
+    args.write_output(&plain, Format::Raw)
+}
+
+fn detect_ecb(args: &Arguments) -> Result<()> {
+    let format = args.format("in-format", Format::Hex)?;
+    let input = match args.option("in") {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?,
+        None => io::read_to_string(io::stdin()).map_err(|e| format!("cannot read stdin: {e}"))?,
+    };
+
+    let mut report = String::new();
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let cipher = format.decode(line.as_bytes())?;
+        let repeated = aes_ecb_detector(&cipher)
+            .map_err(|_| format!("line {} is not a multiple of 16 bytes", i + 1))?;
+        if repeated > 0 {
+            report += &format!("line {}: {} repeated blocks\n", i + 1, repeated);
+        }
+    }
+    args.write_output(report.as_bytes(), Format::Raw)
+}
+
+// Key files have one "name hex-value" pair per line, with e, n and d for a
+// private key. Encryption only needs e and n.
+fn read_rsa_key(path: &str) -> Result<HashMap<String, Integer>> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (name, value) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("malformed key line {line}"))?;
+            let value = Integer::from_str_radix(value.trim(), 16)
+                .map_err(|_| format!("invalid value for {name}"))?;
+            Ok((name.to_string(), value))
+        })
+        .collect()
+}
+
+fn key_component<'a>(key: &'a HashMap<String, Integer>, name: &str) -> Result<&'a Integer> {
+    key.get(name)
+        .ok_or_else(|| format!("key file has no {name}"))
+}
+
+fn rsa(args: &Arguments) -> Result<()> {
+    match args.positional(1, "rsa command")? {
+        "keygen" => {
+            // smaller moduli leave no room for two distinct primes
+            let bits = args.number("bits", 1024)?;
+            if bits < 64 || !bits.is_multiple_of(2) {
+                return Err("--bits must be an even number of at least 64".to_string());
+            }
+            let (public_key, private_key) = rsa_keygen(bits);
+            let key = format!(
+                "e {:x}\nn {:x}\nd {:x}\n",
+                public_key.e,
+                public_key.n,
+                private_key.d()
+            );
+            match args.option("out") {
+                Some(path) => fs::write(path, key).map_err(|e| format!("cannot write {path}: {e}")),
+                None => {
+                    print!("{key}");
+                    Ok(())
+                }
+            }
+        }
+        "encrypt" => {
+            let key = read_rsa_key(args.required("key")?)?;
+            let public_key = RsaPublic {
+                e: key_component(&key, "e")?.clone(),
+                n: key_component(&key, "n")?.clone(),
+            };
+            let input = args.read_input()?;
+            if from_bytes(&input) >= public_key.n {
+                return Err("message is too long for the key".to_string());
+            }
+            args.write_output(&to_bytes(&public_key.encrypt_bytes(&input)), Format::Raw)
+        }
+        "decrypt" => {
+            let key = read_rsa_key(args.required("key")?)?;
+            let private_key = RsaPrivate::new(key_component(&key, "d")?, key_component(&key, "n")?);
+            let input = args.read_input()?;
+            args.write_output(&private_key.decrypt_bytes(&from_bytes(&input)), Format::Raw)
+        }
+        command => Err(format!("unknown rsa command {command}")),
+    }
+}
+
+fn run(args: &Arguments) -> Result<()> {
+    match args.positional(0, "command")? {
+        "encrypt" => cipher(args, true),
+        "decrypt" => cipher(args, false),
+        "hash" => hash(args),
+        "break-xor" => break_xor(args),
+        "detect-ecb" => detect_ecb(args),
+        "rsa" => rsa(args),
+        "help" => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        command => Err(format!("unknown command {command}")),
+    }
+}
+
+fn main() {
+    let result = Arguments::parse(env::args().skip(1)).and_then(|args| run(&args));
+    if let Err(message) = result {
+        eprintln!("error: {message}\n\n{USAGE}");
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn arguments(args: &[&str]) -> Result<Arguments> {
+        Arguments::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn temp_path(name: &str) -> String {
+        env::temp_dir()
+            .join(format!("cryptopals-{}-{name}", process::id()))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    // Runs a command the way main does, with input and output going through files.
+    fn run_command(name: &str, args: &[&str], input: &[u8]) -> Result<Vec<u8>> {
+        let in_path = temp_path(&format!("{name}.in"));
+        let out_path = temp_path(&format!("{name}.out"));
+        fs::write(&in_path, input).unwrap();
+
+        let args = [args, &["--in", &in_path, "--out", &out_path]].concat();
+        let result = run(&arguments(&args)?).map(|_| fs::read(&out_path).unwrap());
+
+        fs::remove_file(&in_path).unwrap();
+        let _ = fs::remove_file(&out_path);
+        result
+    }
+
+    #[test]
+    fn formats() {
+        let data = b"any carnal pleas";
+        for format in [Format::Raw, Format::Hex, Format::Base64] {
+            assert_eq!(format.decode(&format.encode(data)).unwrap(), data);
+        }
+        assert_eq!(Format::Hex.decode(b"6869\n21").unwrap(), b"hi!");
+        assert!(Format::Hex.decode(b"zz").is_err());
+        assert!(Format::parse("rot13").is_err());
+    }
+
+    #[test]
+    fn parse_arguments() {
+        let args =
+            arguments(&["hash", "hmac", "--key", "6b6579", "--out-format", "base64"]).unwrap();
+        assert_eq!(args.positional(0, "command").unwrap(), "hash");
+        assert_eq!(args.positional(1, "hash algorithm").unwrap(), "hmac");
+        assert_eq!(args.key("key").unwrap(), b"key");
+        assert!(args.format("out-format", Format::Hex).unwrap() == Format::Base64);
+        assert_eq!(args.number("bits", 1024).unwrap(), 1024);
+        assert!(args.positional(2, "nothing").is_err());
+
+        assert!(arguments(&["encrypt", "--key"]).is_err());
+    }
+
+    #[test]
+    fn cipher_command() {
+        let plain = b"Ice ice baby, vanilla ice ice baby";
+        let key = "000102030405060708090a0b0c0d0e0f";
+        let iv = "0f0e0d0c0b0a09080706050403020100";
+
+        for mode in ["ecb", "cbc", "ctr"] {
+            let cipher = run_command(
+                &format!("encrypt-{mode}"),
+                &["encrypt", "--mode", mode, "--key", key, "--iv", iv],
+                plain,
+            )
+            .unwrap();
+            assert_ne!(&cipher[..16], &plain[..16]);
+
+            let decrypted = run_command(
+                &format!("decrypt-{mode}"),
+                &["decrypt", "--mode", mode, "--key", key, "--iv", iv],
+                &cipher,
+            )
+            .unwrap();
+            assert_eq!(decrypted, plain);
+        }
+
+        assert!(run_command(
+            "encrypt-xts",
+            &["encrypt", "--mode", "xts", "--key", key],
+            plain
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn hash_command() {
+        assert_eq!(
+            run_command("hash-sha1", &["hash", "sha1"], b"abc").unwrap(),
+            b"a9993e364706816aba3e25717850c26c9cd0d89d\n"
+        );
+        assert_eq!(
+            run_command("hash-md4", &["hash", "md4"], b"abc").unwrap(),
+            b"a448017aaf21d8525fc10ae87aa6729d\n"
+        );
+        assert_eq!(
+            run_command(
+                "hash-hmac",
+                &["hash", "hmac", "--key", "6b6579"],
+                b"The quick brown fox jumps over the lazy dog"
+            )
+            .unwrap(),
+            b"de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9\n"
+        );
+    }
+
+    #[test]
+    fn detect_ecb_command() {
+        let input = format!(
+            "{}\n{}\n",
+            hex::encode(b"YELLOW SUBMARINEyellow submarine"),
+            hex::encode(b"YELLOW SUBMARINE".repeat(3))
+        );
+        assert_eq!(
+            run_command("detect-ecb", &["detect-ecb"], input.as_bytes()).unwrap(),
+            b"line 2: 2 repeated blocks\n"
+        );
+        assert!(run_command("detect-ecb-short", &["detect-ecb"], b"0011\n").is_err());
+    }
+
+    #[test]
+    fn rsa_command() {
+        assert!(run_command("rsa-small", &["rsa", "keygen", "--bits", "8"], b"").is_err());
+        assert!(run_command("rsa-odd", &["rsa", "keygen", "--bits", "513"], b"").is_err());
+
+        let key = run_command("rsa-keygen", &["rsa", "keygen", "--bits", "512"], b"").unwrap();
+        let key_path = temp_path("rsa.key");
+        fs::write(&key_path, key).unwrap();
+
+        let plain = b"attack at dawn";
+        let cipher = run_command(
+            "rsa-encrypt",
+            &["rsa", "encrypt", "--key", &key_path],
+            plain,
+        )
+        .unwrap();
+        let decrypted = run_command(
+            "rsa-decrypt",
+            &["rsa", "decrypt", "--key", &key_path],
+            &cipher,
+        )
+        .unwrap();
+        fs::remove_file(&key_path).unwrap();
+
+        assert_eq!(decrypted, plain);
+    }
+}
@@ -1,79 +1,88 @@
-use std::{collections::HashMap, convert::TryInto};
-
-use aes::{
-    cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit},
-    Aes128, Block,
+use std::collections::HashMap;
+
+use crate::{
+    s02::padding::pkcs7_padding,
+    util::{
+        block_cipher::{aes128, BlockCipher},
+        error::{CryptoError, Result},
+    },
 };
 
-use crate::s02::padding::pkcs7_padding;
+pub fn aes128_ecb_encode(plain: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    ecb_encode(&aes128(key)?, plain)
+}
+
+pub fn aes128_ecb_decode(cipher: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    ecb_decode(&aes128(key)?, cipher)
+}
 
-pub fn aes128_ecb_encode(plain: &[u8], key: &[u8]) -> Vec<u8> {
-    let key_array = GenericArray::from_slice(key);
-    let aes = Aes128::new(key_array);
+pub fn ecb_encode(block_cipher: &impl BlockCipher, plain: &[u8]) -> Result<Vec<u8>> {
+    let block_size = block_cipher.block_size();
 
     let mut res = Vec::<u8>::new();
 
-    for chunk in plain.chunks(16) {
-        let mut block = Block::clone_from_slice(&pkcs7_padding(chunk, 16));
-        aes.encrypt_block(&mut block);
-        res.append(&mut block.to_vec());
+    for chunk in plain.chunks(block_size) {
+        let mut block = pkcs7_padding(chunk, block_size)?;
+        block_cipher.encrypt_block(&mut block);
+        res.append(&mut block);
     }
 
-    if plain.len() % 16 == 0 {
-        let mut block = Block::clone_from_slice(&[16; 16]);
-        aes.encrypt_block(&mut block);
-        res.append(&mut block.to_vec());
+    if plain.len().is_multiple_of(block_size) {
+        let mut block = vec![block_size as u8; block_size];
+        block_cipher.encrypt_block(&mut block);
+        res.append(&mut block);
     }
 
-    res
+    Ok(res)
 }
 
-pub fn aes128_ecb_decode(cipher: &[u8], key: &[u8]) -> Vec<u8> {
-    let key_array = GenericArray::from_slice(key);
-    let aes = Aes128::new(key_array);
+pub fn ecb_decode(block_cipher: &impl BlockCipher, cipher: &[u8]) -> Result<Vec<u8>> {
+    let block_size = block_cipher.block_size();
 
     let mut res = Vec::<u8>::new();
 
-    let blocks = cipher.len() / 16;
-
-    if blocks * 16 != cipher.len() {
-        panic!("input must be a multiple of 16!");
+    if cipher.is_empty() || !cipher.len().is_multiple_of(block_size) {
+        return Err(CryptoError::InvalidCiphertextLength(cipher.len()));
     }
 
-    for chunk in cipher.chunks(16) {
-        let mut block = Block::clone_from_slice(chunk);
-        aes.decrypt_block(&mut block);
-        res.append(&mut block.to_vec());
+    for chunk in cipher.chunks(block_size) {
+        let mut block = chunk.to_vec();
+        block_cipher.decrypt_block(&mut block);
+        res.append(&mut block);
     }
 
-    res
+    Ok(res)
+}
+
+pub fn aes_ecb_detector(cipher: &[u8]) -> Result<usize> {
+    ecb_detector(cipher, 16)
 }
 
-pub fn aes_ecb_detector(cipher: &[u8]) -> usize {
-    let mut map: HashMap<[u8; 16], usize> = HashMap::new();
+pub fn ecb_detector(cipher: &[u8], block_size: usize) -> Result<usize> {
+    let mut map: HashMap<&[u8], usize> = HashMap::new();
 
-    let blocks = cipher.len() / 16;
+    let blocks = cipher.len() / block_size;
 
-    if blocks * 16 != cipher.len() {
-        panic!("input must be a multiple of 16!");
+    if blocks * block_size != cipher.len() {
+        return Err(CryptoError::InvalidCiphertextLength(cipher.len()));
     }
 
-    for chunk in cipher.chunks(16) {
-        let value = map
-            .entry(chunk.try_into().expect("input must be a multiple of 16!"))
-            .or_insert(0);
+    for chunk in cipher.chunks(block_size) {
+        let value = map.entry(chunk).or_insert(0);
         *value += 1;
     }
 
-    let expected_count = (blocks as f64 / f64::powi(2.0, 128)).ceil() as usize;
+    let expected_count = (blocks as f64 / f64::powi(2.0, 8 * block_size as i32)).ceil() as usize;
 
-    map.values().max().unwrap_or(&0) - expected_count
+    Ok(map.values().max().unwrap_or(&0) - expected_count)
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::util::base_64::Base64;
+    use aes::{cipher::KeyInit, Aes256};
+
+    use crate::util::{base_64::Base64, xtea::Xtea};
 
     use super::*;
 
@@ -90,7 +99,7 @@ mod tests {
 
         let key = String::from("YELLOW SUBMARINE");
 
-        let decode = aes128_ecb_decode(input_bytes.to_bytes(), key.as_bytes());
+        let decode = aes128_ecb_decode(input_bytes.to_bytes(), key.as_bytes()).unwrap();
 
         let plain = fs::read_to_string("data/set1/7_plain.txt")
             .expect("Something went wrong reading the plain file");
@@ -107,7 +116,7 @@ mod tests {
 
         let reducer = |previous: (usize, usize), str: (usize, &str)| -> (usize, usize) {
             let bytes = hex::decode(str.1).expect("decoding failed");
-            let res = aes_ecb_detector(&bytes);
+            let res = aes_ecb_detector(&bytes).unwrap();
             if previous.0 < res {
                 println!("New best: {} in line {}, was {}", res, str.0, previous.0);
                 (res, str.0)
@@ -119,4 +128,48 @@ mod tests {
         let res: (usize, usize) = input.lines().enumerate().fold((0, 0), reducer);
         assert_eq!(res.1, 132);
     }
+
+    #[test]
+    fn ecb_other_ciphers() {
+        let plain = "Burning 'em, if you ain't quick and nimble";
+
+        let aes = Aes256::new_from_slice(&[7; 32]).unwrap();
+        let cipher = ecb_encode(&aes, plain.as_bytes()).unwrap();
+        assert_eq!(cipher.len(), 48);
+        assert_eq!(
+            &ecb_decode(&aes, &cipher).unwrap()[..plain.len()],
+            plain.as_bytes()
+        );
+
+        let xtea = Xtea::new(&[7; 16]).unwrap();
+        let cipher = ecb_encode(&xtea, plain.as_bytes()).unwrap();
+        assert_eq!(cipher.len(), 48);
+        assert_eq!(
+            &ecb_decode(&xtea, &cipher).unwrap()[..plain.len()],
+            plain.as_bytes()
+        );
+
+        let repeated = ecb_encode(&xtea, &[0; 64]).unwrap();
+        assert_eq!(ecb_detector(&repeated, 8), Ok(7));
+    }
+
+    #[test]
+    fn ecb_errors() {
+        assert_eq!(
+            aes128_ecb_encode(b"plain", &[0; 15]),
+            Err(CryptoError::InvalidKeyLength(15))
+        );
+        assert_eq!(
+            aes128_ecb_decode(&[0; 17], &[0; 16]),
+            Err(CryptoError::InvalidCiphertextLength(17))
+        );
+        assert_eq!(
+            aes128_ecb_decode(&[], &[0; 16]),
+            Err(CryptoError::InvalidCiphertextLength(0))
+        );
+        assert_eq!(
+            aes_ecb_detector(&[0; 20]),
+            Err(CryptoError::InvalidCiphertextLength(20))
+        );
+    }
 }
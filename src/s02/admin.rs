@@ -2,8 +2,11 @@ use std::str::from_utf8;
 
 use crate::{
     s01::aes_ecb::{aes128_ecb_decode, aes128_ecb_encode},
-    s02::padding::pkcs7_padding,
-    util::generators::generate_aes_key,
+    s02::padding::{pkcs7_padding, remove_pkcs7_padding},
+    util::{
+        error::{CryptoError, Result},
+        generators::generate_aes_key,
+    },
 };
 
 use super::aes_cbc::{aes128_cbc_decode, aes128_cbc_encode};
@@ -19,15 +22,13 @@ impl AdminOracle {
         plain.append(&mut "email=".as_bytes().to_vec());
         plain.append(&mut email.to_vec());
         plain.append(&mut "&uid=10&role=user".as_bytes().to_vec());
-        aes128_ecb_encode(&plain, &self.key)
+        aes128_ecb_encode(&plain, &self.key).unwrap()
     }
-    pub fn decrypt(&self, cipher: &[u8]) -> String {
-        let mut plain = aes128_ecb_decode(cipher, &self.key);
-        let last_byte = *plain.last().unwrap();
-        if (1..16).contains(&last_byte) {
-            plain.truncate(plain.len() - last_byte as usize);
-        }
-        from_utf8(&plain).unwrap().to_string()
+    pub fn decrypt(&self, cipher: &[u8]) -> Result<String> {
+        let plain = remove_pkcs7_padding(&aes128_ecb_decode(cipher, &self.key)?)?;
+        from_utf8(&plain)
+            .map(|plain| plain.to_string())
+            .map_err(|_| CryptoError::InvalidUtf8)
     }
 }
 
@@ -53,11 +54,16 @@ impl AdminOracleCBC {
         plain.append(&mut self.postfix.clone());
 
         let iv = generate_aes_key();
-        (iv.clone(), aes128_cbc_encode(&plain, &self.key, &iv))
+        (
+            iv.clone(),
+            aes128_cbc_encode(&plain, &self.key, &iv).unwrap(),
+        )
     }
 
     pub fn is_admin(&self, iv: &[u8], cipher: &[u8]) -> bool {
-        let plain = aes128_cbc_decode(cipher, &self.key, iv);
+        let Ok(plain) = aes128_cbc_decode(cipher, &self.key, iv) else {
+            return false;
+        };
         for substring in plain.split(|byte| *byte == b';') {
             if substring == b"admin=true" {
                 return true;
@@ -72,7 +78,7 @@ pub fn cut_and_paste() -> String {
         key: generate_aes_key(),
     };
 
-    let mut admin = pkcs7_padding("admin".as_bytes(), 16);
+    let mut admin = pkcs7_padding("admin".as_bytes(), 16).unwrap();
     let dummy_email = "1234@5.com";
     assert!(dummy_email.as_bytes().len() == 10);
 
@@ -89,7 +95,7 @@ pub fn cut_and_paste() -> String {
 
     profile.splice(32.., admin_cipher.iter().cloned());
 
-    oracle.decrypt(&profile)
+    oracle.decrypt(&profile).unwrap()
 }
 
 #[cfg(test)]
@@ -102,6 +108,29 @@ mod tests {
         assert!(cut_and_paste().contains("role=admin"));
     }
 
+    #[test]
+    fn admin_oracle_errors() {
+        let oracle = AdminOracle {
+            key: generate_aes_key(),
+        };
+        assert_eq!(
+            oracle.decrypt(&[]),
+            Err(CryptoError::InvalidCiphertextLength(0))
+        );
+        assert_eq!(
+            oracle.decrypt(&[0; 17]),
+            Err(CryptoError::InvalidCiphertextLength(17))
+        );
+
+        // garbage fails the padding or, failing that, the UTF-8 check
+        let garbage = (0..32).map(|_| rand::random()).collect::<Vec<u8>>();
+        assert!(oracle.decrypt(&garbage).is_err());
+
+        // valid padding around bytes that are not UTF-8
+        let profile = oracle.profile_for(b"\xff\xfe");
+        assert_eq!(oracle.decrypt(&profile), Err(CryptoError::InvalidUtf8));
+    }
+
     #[test]
     fn s02e08() {
         let prefix = b"comment1=cooking MCs;userdata=";
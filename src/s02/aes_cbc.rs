@@ -1,67 +1,81 @@
-use aes::{
-    cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit},
-    Aes128, Block,
+use crate::util::{
+    block_cipher::{aes128, BlockCipher},
+    error::{CryptoError, Result},
+    xor,
 };
 
-use crate::util::xor;
-
 use super::padding::pkcs7_padding;
 
-pub fn aes128_cbc_encode(plain: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
-    let key_array = GenericArray::from_slice(key);
-    let aes = Aes128::new(key_array);
+pub fn aes128_cbc_encode(plain: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    cbc_encode(&aes128(key)?, plain, iv)
+}
+
+pub fn aes128_cbc_decode(cipher: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    cbc_decode(&aes128(key)?, cipher, iv)
+}
+
+pub fn cbc_encode(block_cipher: &impl BlockCipher, plain: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    let block_size = block_cipher.block_size();
 
     let mut res = Vec::<u8>::new();
 
     let mut xor_text = Vec::<u8>::from(iv);
 
-    if iv.len() % 16 != 0 {
-        panic!("input must be multiple of 16!");
+    if iv.len() != block_size {
+        return Err(CryptoError::InvalidIvLength(iv.len()));
     }
 
-    for chunk in plain.chunks(16) {
-        let mut block = Block::clone_from_slice(&xor::xor(&pkcs7_padding(chunk, 16), &xor_text));
-        aes.encrypt_block(&mut block);
-        xor_text = block.to_vec();
-        res.append(&mut block.to_vec());
+    for chunk in plain.chunks(block_size) {
+        let mut block = xor::xor(&pkcs7_padding(chunk, block_size)?, &xor_text);
+        block_cipher.encrypt_block(&mut block);
+        xor_text = block.clone();
+        res.append(&mut block);
     }
 
-    if plain.len() % 16 == 0 {
-        let mut block = Block::clone_from_slice(&xor::xor(&[16; 16], &xor_text));
-        aes.encrypt_block(&mut block);
-        res.append(&mut block.to_vec());
+    if plain.len().is_multiple_of(block_size) {
+        let mut block = xor::xor(&vec![block_size as u8; block_size], &xor_text);
+        block_cipher.encrypt_block(&mut block);
+        res.append(&mut block);
     }
 
-    res
+    Ok(res)
 }
 
-pub fn aes128_cbc_decode(cipher: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
-    let key_array = GenericArray::from_slice(key);
-    let aes = Aes128::new(key_array);
+pub fn cbc_decode(block_cipher: &impl BlockCipher, cipher: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    let block_size = block_cipher.block_size();
 
     let mut res = Vec::<u8>::new();
 
     let mut xor_text = Vec::<u8>::from(iv);
 
-    if cipher.len() % 16 != 0 {
-        panic!("input must be multiple of 16!");
+    if iv.len() != block_size {
+        return Err(CryptoError::InvalidIvLength(iv.len()));
+    }
+
+    if cipher.is_empty() || !cipher.len().is_multiple_of(block_size) {
+        return Err(CryptoError::InvalidCiphertextLength(cipher.len()));
     }
 
-    for chunk in cipher.chunks(16) {
-        let mut block = Block::clone_from_slice(chunk);
-        aes.decrypt_block(&mut block);
+    for chunk in cipher.chunks(block_size) {
+        let mut block = chunk.to_vec();
+        block_cipher.decrypt_block(&mut block);
         res.append(&mut xor::xor(&block, &xor_text));
         xor_text = Vec::<u8>::from(chunk);
     }
 
-    res
+    Ok(res)
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
 
-    use crate::util::base_64::Base64;
+    use aes::{cipher::KeyInit, Aes192};
+
+    use crate::{
+        s02::padding::remove_pkcs7_padding,
+        util::{base_64::Base64, xtea::Xtea},
+    };
 
     use super::*;
 
@@ -78,11 +92,47 @@ mod tests {
 
         let input_bytes = Base64::new_from_string(&input).unwrap();
 
-        let decoded = aes128_cbc_decode(input_bytes.to_bytes(), key.as_bytes(), &iv);
+        let decoded = aes128_cbc_decode(input_bytes.to_bytes(), key.as_bytes(), &iv).unwrap();
 
         let plain = fs::read_to_string("data/set1/7_plain.txt")
             .expect("Something went wrong reading the challenge file");
 
         assert_eq!(plain, std::str::from_utf8(&decoded).unwrap())
     }
+
+    #[test]
+    fn cbc_other_ciphers() {
+        let plain = "I go crazy when I hear a cymbal";
+
+        let aes = Aes192::new_from_slice(&[3; 24]).unwrap();
+        let iv = [1; 16];
+        let cipher = cbc_encode(&aes, plain.as_bytes(), &iv).unwrap();
+        let decoded = remove_pkcs7_padding(&cbc_decode(&aes, &cipher, &iv).unwrap()).unwrap();
+        assert_eq!(decoded, plain.as_bytes());
+
+        let xtea = Xtea::new(&[3; 16]).unwrap();
+        let iv = [1; 8];
+        let cipher = cbc_encode(&xtea, plain.as_bytes(), &iv).unwrap();
+        assert_eq!(cipher.len(), 32);
+        let decoded = remove_pkcs7_padding(&cbc_decode(&xtea, &cipher, &iv).unwrap()).unwrap();
+        assert_eq!(decoded, plain.as_bytes());
+    }
+
+    #[test]
+    fn cbc_errors() {
+        let key = [0; 16];
+        let iv = [0; 16];
+        assert_eq!(
+            aes128_cbc_encode(b"plain", &key, &iv[..8]),
+            Err(CryptoError::InvalidIvLength(8))
+        );
+        assert_eq!(
+            aes128_cbc_decode(&[0; 16], &key[..8], &iv),
+            Err(CryptoError::InvalidKeyLength(8))
+        );
+        assert_eq!(
+            aes128_cbc_decode(&[0; 20], &key, &iv),
+            Err(CryptoError::InvalidCiphertextLength(20))
+        );
+    }
 }
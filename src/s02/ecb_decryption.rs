@@ -1,57 +1,70 @@
+use aes::{cipher::KeyInit, Aes128};
 use indicatif::ProgressIterator;
 use std::collections::{HashMap, HashSet};
 
 use crate::{
-    s01::aes_ecb::{aes128_ecb_encode, aes_ecb_detector},
-    util::progress_bar::create_progress_bar,
+    s01::aes_ecb::{ecb_detector, ecb_encode},
+    util::{block_cipher::BlockCipher, progress_bar::create_progress_bar},
 };
 
 use super::oracle::EncryptionOracle;
 
-pub struct ECBOracleSimple {
+pub struct ECBOracleSimple<C: BlockCipher = Aes128> {
     secret: Vec<u8>,
-    key: Vec<u8>,
+    cipher: C,
 }
 
 impl ECBOracleSimple {
     pub fn new(secret: &[u8], key: &[u8]) -> ECBOracleSimple {
+        ECBOracleSimple::with_cipher(secret, Aes128::new_from_slice(key).unwrap())
+    }
+}
+
+impl<C: BlockCipher> ECBOracleSimple<C> {
+    pub fn with_cipher(secret: &[u8], cipher: C) -> ECBOracleSimple<C> {
         ECBOracleSimple {
             secret: secret.to_vec(),
-            key: key.to_vec(),
+            cipher,
         }
     }
 }
 
-impl EncryptionOracle for ECBOracleSimple {
+impl<C: BlockCipher> EncryptionOracle for ECBOracleSimple<C> {
     fn encrypt(&self, input: &[u8]) -> Vec<u8> {
         let mut new_plain = input.to_vec();
         new_plain.append(&mut self.secret.clone());
-        aes128_ecb_encode(&new_plain, &self.key)
+        ecb_encode(&self.cipher, &new_plain).unwrap()
     }
 }
 
-pub struct ECBOracleHard {
+pub struct ECBOracleHard<C: BlockCipher = Aes128> {
     secret: Vec<u8>,
-    key: Vec<u8>,
+    cipher: C,
     random: Vec<u8>,
 }
 
 impl ECBOracleHard {
     pub fn new(secret: &[u8], key: &[u8], random: &[u8]) -> ECBOracleHard {
+        ECBOracleHard::with_cipher(secret, Aes128::new_from_slice(key).unwrap(), random)
+    }
+}
+
+impl<C: BlockCipher> ECBOracleHard<C> {
+    pub fn with_cipher(secret: &[u8], cipher: C, random: &[u8]) -> ECBOracleHard<C> {
         ECBOracleHard {
             secret: secret.to_vec(),
-            key: key.to_vec(),
+            cipher,
             random: random.to_vec(),
         }
     }
 }
 
-impl EncryptionOracle for ECBOracleHard {
+impl<C: BlockCipher> EncryptionOracle for ECBOracleHard<C> {
     fn encrypt(&self, input: &[u8]) -> Vec<u8> {
         let mut new_plain = self.random.clone();
         new_plain.append(&mut input.to_vec());
         new_plain.append(&mut self.secret.clone());
-        aes128_ecb_encode(&new_plain, &self.key)
+        ecb_encode(&self.cipher, &new_plain).unwrap()
     }
 }
 
@@ -63,7 +76,7 @@ pub fn decrypt_ecb(oracle: &impl EncryptionOracle) -> Vec<u8> {
     let blocksize = get_blocksize(oracle);
     println!(" blocksize: {blocksize}...");
 
-    let count = aes_ecb_detector(&oracle.encrypt(&vec![0; 10 * blocksize]));
+    let count = ecb_detector(&oracle.encrypt(&vec![0; 10 * blocksize]), blocksize).unwrap();
     if count >= 5 {
         println!(" detected ECB...");
     } else {
@@ -118,7 +131,7 @@ fn get_random_prefix_length(oracle: &impl EncryptionOracle, blocksize: usize) ->
     for i in 0..blocksize {
         difference_map.insert(
             i,
-            aes_ecb_detector(&oracle.encrypt(&vec![0; 10 * blocksize + i])),
+            ecb_detector(&oracle.encrypt(&vec![0; 10 * blocksize + i]), blocksize).unwrap(),
         );
     }
     let mut difference_to_blocksize = 0;
@@ -216,9 +229,10 @@ mod tests {
 
     use std::{fs, str::from_utf8};
 
+    use aes::Aes256;
     use rand::{Rng, RngCore};
 
-    use crate::util::{base_64::Base64, generators::generate_aes_key};
+    use crate::util::{base_64::Base64, generators::generate_aes_key, xtea::Xtea};
 
     use super::*;
 
@@ -228,10 +242,10 @@ mod tests {
             .expect("Something went wrong reading the challenge file");
         let input = input.replace('\n', "");
 
-        let oracle = ECBOracleSimple {
-            secret: Base64::new_from_string(&input).unwrap().to_bytes().to_vec(),
-            key: generate_aes_key(),
-        };
+        let oracle = ECBOracleSimple::new(
+            Base64::new_from_string(&input).unwrap().to_bytes(),
+            &generate_aes_key(),
+        );
 
         let dec = decrypt_ecb(&oracle);
 
@@ -253,11 +267,11 @@ mod tests {
         let mut random_vec = vec![0; random_vec_len];
         rand::thread_rng().fill_bytes(&mut random_vec);
 
-        let oracle = ECBOracleHard {
-            secret: Base64::new_from_string(&input).unwrap().to_bytes().to_vec(),
-            key: generate_aes_key(),
-            random: random_vec,
-        };
+        let oracle = ECBOracleHard::new(
+            Base64::new_from_string(&input).unwrap().to_bytes(),
+            &generate_aes_key(),
+            &random_vec,
+        );
 
         let dec = decrypt_ecb(&oracle);
 
@@ -268,4 +282,38 @@ mod tests {
 
         assert_eq!(dec_str, &plain);
     }
+
+    #[test]
+    fn ecb_decryption_other_ciphers() {
+        let input = fs::read_to_string("data/set2/4.txt")
+            .expect("Something went wrong reading the challenge file");
+        let input = input.replace('\n', "");
+        let secret = Base64::new_from_string(&input).unwrap();
+
+        let plain = fs::read_to_string("data/set2/4_plain.txt")
+            .expect("Something went wrong reading the result file");
+
+        let random_vec_len = rand::thread_rng().gen_range(0..32);
+        let mut random_vec = vec![0; random_vec_len];
+        rand::thread_rng().fill_bytes(&mut random_vec);
+
+        let mut key = vec![0; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        let oracle = ECBOracleHard::with_cipher(
+            secret.to_bytes(),
+            Aes256::new_from_slice(&key).unwrap(),
+            &random_vec,
+        );
+        let dec = decrypt_ecb(&oracle);
+        assert_eq!(from_utf8(&dec).unwrap(), &plain);
+
+        let oracle = ECBOracleHard::with_cipher(
+            secret.to_bytes(),
+            Xtea::new(&key[..16]).unwrap(),
+            &random_vec,
+        );
+        let dec = decrypt_ecb(&oracle);
+        assert_eq!(from_utf8(&dec).unwrap(), &plain);
+    }
 }
@@ -27,10 +27,10 @@ pub fn encryption_oracle(input: &[u8]) -> (Vec<u8>, bool) {
 
     let choice: bool = rng.gen();
     if choice {
-        (aes128_ecb_encode(&plain, &aes_key), choice)
+        (aes128_ecb_encode(&plain, &aes_key).unwrap(), choice)
     } else {
         (
-            aes128_cbc_encode(&plain, &aes_key, &generate_aes_key()),
+            aes128_cbc_encode(&plain, &aes_key, &generate_aes_key()).unwrap(),
             choice,
         )
     }
@@ -40,7 +40,7 @@ pub fn detect_ecb_cbc() -> (bool, bool) {
     let plain = [0; 160];
 
     let result = encryption_oracle(&plain);
-    let count = aes_ecb_detector(&result.0);
+    let count = aes_ecb_detector(&result.0).unwrap();
 
     if count >= 5 {
         (true, result.1)
@@ -1,10 +1,14 @@
-use std::fmt;
+use crate::util::error::{CryptoError, Result};
 
-pub fn pkcs7_padding(text: &[u8], padding_length: usize) -> Vec<u8> {
+pub fn pkcs7_padding(text: &[u8], padding_length: usize) -> Result<Vec<u8>> {
     let mut res = Vec::<u8>::from(text);
 
+    // the padding length has to fit into a single byte
+    if padding_length > 255 {
+        return Err(CryptoError::InvalidBlockSize(padding_length));
+    }
     if text.len() > padding_length {
-        panic!("Text length bigger than padding length")
+        return Err(CryptoError::TextTooLong(text.len()));
     }
 
     let length = padding_length - text.len();
@@ -13,40 +17,21 @@ pub fn pkcs7_padding(text: &[u8], padding_length: usize) -> Vec<u8> {
 
     res.append(&mut vec![padding_bytes; padding_bytes as usize]);
 
-    res
-}
-
-type Result<T> = std::result::Result<T, PKCS7Error>;
-
-// Define our error types. These may be customized for our error handling cases.
-// Now we will be able to write our own errors, defer to an underlying error
-// implementation, or do something in between.
-#[derive(Debug, Clone)]
-pub struct PKCS7Error;
-
-// Generation of an error is completely separate from how it is displayed.
-// There's no need to be concerned about cluttering complex logic with the display style.
-//
-// Note that we don't store any extra info about the errors. This means we can't state
-// which string failed to parse without modifying our types to carry that information.
-impl fmt::Display for PKCS7Error {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "invalid pkcs7 padding")
-    }
+    Ok(res)
 }
 
 pub fn remove_pkcs7_padding(text: &[u8]) -> Result<Vec<u8>> {
-    let padding_byte = *text.last().unwrap();
+    let padding_byte = *text.last().ok_or(CryptoError::InvalidPadding)?;
     if padding_byte == 0 {
-        return Err(PKCS7Error);
+        return Err(CryptoError::InvalidPadding);
     }
     if padding_byte as usize > text.len() {
-        return Err(PKCS7Error);
+        return Err(CryptoError::InvalidPadding);
     }
     let padding_start = text.len() - padding_byte as usize;
     let res = text[0..padding_start].to_vec();
     if text[padding_start..] != vec![padding_byte; padding_byte as usize] {
-        return Err(PKCS7Error);
+        return Err(CryptoError::InvalidPadding);
     }
     Ok(res)
 }
@@ -59,9 +44,19 @@ mod tests {
     fn s02e01() {
         let input = String::from("YELLOW SUBMARINE");
 
-        let res = pkcs7_padding(input.as_bytes(), 20);
+        let res = pkcs7_padding(input.as_bytes(), 20).unwrap();
 
         assert_eq!("59454c4c4f57205355424d4152494e4504040404", hex::encode(res));
+
+        assert_eq!(
+            pkcs7_padding(input.as_bytes(), 8),
+            Err(CryptoError::TextTooLong(16))
+        );
+        assert_eq!(
+            pkcs7_padding(input.as_bytes(), 300),
+            Err(CryptoError::InvalidBlockSize(300))
+        );
+        assert_eq!(pkcs7_padding(b"", 255).unwrap(), vec![255; 255]);
     }
 
     #[test]
@@ -79,5 +74,7 @@ mod tests {
         let input3 = b"ICE ICE BABY\x01\x02\x03\x04";
 
         assert!(remove_pkcs7_padding(input3).is_err());
+
+        assert_eq!(remove_pkcs7_padding(b""), Err(CryptoError::InvalidPadding));
     }
 }
@@ -1,11 +1,10 @@
-use aes::{
-    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
-    Aes128, Block,
+use crate::util::{
+    block_cipher::{aes128, BlockCipher},
+    error::{CryptoError, Result},
+    xor,
 };
 
-use crate::util::xor;
-
-pub fn aes128_ctr_encode(plain: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+pub fn aes128_ctr_encode(plain: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
     aes128_ctr_xor(plain, key, iv)
 }
 
@@ -14,11 +13,11 @@ pub fn aes128_ctr_encode_with_full_iv(
     key: &[u8],
     iv: &[u8],
     iv_len: usize,
-) -> Vec<u8> {
+) -> Result<Vec<u8>> {
     aes128_ctr_xor_with_full_iv(plain, key, iv, iv_len)
 }
 
-pub fn aes128_ctr_decode(cipher: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+pub fn aes128_ctr_decode(cipher: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
     aes128_ctr_xor(cipher, key, iv)
 }
 
@@ -27,40 +26,80 @@ pub fn aes128_ctr_decode_with_full_iv(
     key: &[u8],
     iv: &[u8],
     iv_len: usize,
-) -> Vec<u8> {
+) -> Result<Vec<u8>> {
     aes128_ctr_xor_with_full_iv(cipher, key, iv, iv_len)
 }
 
-fn aes128_ctr_xor(input: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+fn aes128_ctr_xor(input: &[u8], key: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    ctr_xor(&aes128(key)?, input, iv)
+}
+
+fn aes128_ctr_xor_with_full_iv(
+    input: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    iv_len: usize,
+) -> Result<Vec<u8>> {
+    ctr_xor_with_full_iv(&aes128(key)?, input, iv, iv_len)
+}
+
+pub fn ctr_encode(block_cipher: &impl BlockCipher, plain: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    ctr_xor(block_cipher, plain, iv)
+}
+
+pub fn ctr_decode(block_cipher: &impl BlockCipher, cipher: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
+    ctr_xor(block_cipher, cipher, iv)
+}
+
+pub fn ctr_xor(block_cipher: &impl BlockCipher, input: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
     let iv_len = iv.len();
+    if iv_len > block_cipher.block_size() {
+        return Err(CryptoError::InvalidIvLength(iv_len));
+    }
 
     let mut iv: Vec<u8> = iv.to_vec();
-    let mut append_zero = vec![0; 16 - iv_len];
+    let mut append_zero = vec![0; block_cipher.block_size() - iv_len];
     iv.append(&mut append_zero);
 
-    aes128_ctr_xor_with_full_iv(input, key, &iv, iv_len)
+    ctr_xor_with_full_iv(block_cipher, input, &iv, iv_len)
 }
 
-fn aes128_ctr_xor_with_full_iv(input: &[u8], key: &[u8], iv: &[u8], iv_len: usize) -> Vec<u8> {
-    if iv.len() != 16 {
-        panic!("IV has to be 16 Bytes long!");
+pub fn ctr_xor_with_full_iv(
+    block_cipher: &impl BlockCipher,
+    input: &[u8],
+    iv: &[u8],
+    iv_len: usize,
+) -> Result<Vec<u8>> {
+    if iv_len > iv.len() {
+        return Err(CryptoError::InvalidIvLength(iv_len));
+    }
+    ctr_xor_with_increment(block_cipher, input, iv, |iv| increment_iv(iv, iv_len))
+}
+
+pub fn ctr_xor_with_increment(
+    block_cipher: &impl BlockCipher,
+    input: &[u8],
+    iv: &[u8],
+    increment: impl Fn(&mut [u8]),
+) -> Result<Vec<u8>> {
+    let block_size = block_cipher.block_size();
+    if iv.len() != block_size {
+        return Err(CryptoError::InvalidIvLength(iv.len()));
     }
-    let key_array = GenericArray::from_slice(key);
-    let aes = Aes128::new(key_array);
     let mut res = Vec::<u8>::new();
 
     let mut iv: Vec<u8> = iv.to_vec();
 
-    for chunk in input.chunks(16) {
-        let mut block = Block::clone_from_slice(&iv);
-        aes.encrypt_block(&mut block);
+    for chunk in input.chunks(block_size) {
+        let mut block = iv.clone();
+        block_cipher.encrypt_block(&mut block);
         res.append(&mut xor::xor(chunk, &block));
-        increment_iv(&mut iv, iv_len);
+        increment(&mut iv);
     }
-    res
+    Ok(res)
 }
 
-pub fn increment_iv(iv: &mut Vec<u8>, original_len: usize) {
+pub fn increment_iv(iv: &mut [u8], original_len: usize) {
     let mut overflow = true;
     let mut i = original_len;
     while overflow && i < iv.len() {
@@ -74,11 +113,24 @@ pub fn increment_iv(iv: &mut Vec<u8>, original_len: usize) {
     }
 }
 
+pub fn increment_iv_big_endian(iv: &mut [u8], counter_len: usize) {
+    for byte in iv.iter_mut().rev().take(counter_len) {
+        if *byte == 255 {
+            *byte = 0;
+        } else {
+            *byte += 1;
+            return;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::from_utf8;
 
-    use crate::util::base_64::Base64;
+    use aes::{cipher::KeyInit, Aes256};
+
+    use crate::util::{base_64::Base64, xtea::Xtea};
 
     use super::*;
 
@@ -93,13 +145,51 @@ mod tests {
 
         let key = "YELLOW SUBMARINE";
 
-        let dec = aes128_ctr_decode(secret.to_bytes(), key.as_bytes(), &iv);
+        let dec = aes128_ctr_decode(secret.to_bytes(), key.as_bytes(), &iv).unwrap();
 
         let plain = "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ";
         assert_eq!(plain, from_utf8(&dec).unwrap());
 
-        let enc = aes128_ctr_encode(plain.as_bytes(), key.as_bytes(), &iv);
+        let enc = aes128_ctr_encode(plain.as_bytes(), key.as_bytes(), &iv).unwrap();
 
         assert_eq!(secret.to_bytes(), enc);
     }
+
+    #[test]
+    fn ctr_other_ciphers() {
+        let plain = "Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby ";
+
+        let aes = Aes256::new_from_slice(&[5; 32]).unwrap();
+        let iv = [0; 8];
+        let cipher = ctr_encode(&aes, plain.as_bytes(), &iv).unwrap();
+        assert_eq!(cipher.len(), plain.len());
+        assert_eq!(ctr_decode(&aes, &cipher, &iv).unwrap(), plain.as_bytes());
+
+        let xtea = Xtea::new(&[5; 16]).unwrap();
+        let iv = [0; 4];
+        let cipher = ctr_encode(&xtea, plain.as_bytes(), &iv).unwrap();
+        assert_eq!(cipher.len(), plain.len());
+        assert_eq!(ctr_decode(&xtea, &cipher, &iv).unwrap(), plain.as_bytes());
+    }
+
+    #[test]
+    fn ctr_errors() {
+        let key = [0; 16];
+        assert_eq!(
+            aes128_ctr_encode(b"plain", &key, &[0; 17]),
+            Err(CryptoError::InvalidIvLength(17))
+        );
+        assert_eq!(
+            aes128_ctr_encode_with_full_iv(b"plain", &key, &[0; 8], 8),
+            Err(CryptoError::InvalidIvLength(8))
+        );
+        assert_eq!(
+            aes128_ctr_decode_with_full_iv(b"plain", &key, &[0; 16], 17),
+            Err(CryptoError::InvalidIvLength(17))
+        );
+        assert_eq!(
+            aes128_ctr_decode(b"plain", &key[..1], &[0; 8]),
+            Err(CryptoError::InvalidKeyLength(1))
+        );
+    }
 }
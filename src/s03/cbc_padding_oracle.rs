@@ -1,54 +1,73 @@
+use aes::{cipher::KeyInit, Aes128};
 use indicatif::ProgressIterator;
+use rand::RngCore;
 
 use crate::{
     s02::{
-        aes_cbc::{aes128_cbc_decode, aes128_cbc_encode},
+        aes_cbc::{cbc_decode, cbc_encode},
         padding::remove_pkcs7_padding,
     },
-    util::{generators::generate_aes_key, progress_bar::create_progress_bar, xor::xor},
+    util::{block_cipher::BlockCipher, progress_bar::create_progress_bar, xor::xor},
 };
 
-pub struct CBCPaddingOracle {
-    key: Vec<u8>,
+pub struct CBCPaddingOracle<C: BlockCipher = Aes128> {
+    cipher: C,
     plain: Vec<u8>,
 }
 
 impl CBCPaddingOracle {
     pub fn new(key: &[u8], plain: &[u8]) -> CBCPaddingOracle {
+        CBCPaddingOracle::with_cipher(Aes128::new_from_slice(key).unwrap(), plain)
+    }
+}
+
+impl<C: BlockCipher> CBCPaddingOracle<C> {
+    pub fn with_cipher(cipher: C, plain: &[u8]) -> CBCPaddingOracle<C> {
         CBCPaddingOracle {
-            key: key.to_vec(),
+            cipher,
             plain: plain.to_vec(),
         }
     }
+
+    pub fn block_size(&self) -> usize {
+        self.cipher.block_size()
+    }
+
     pub fn encrypt(&self) -> (Vec<u8>, Vec<u8>) {
-        let iv = generate_aes_key();
-        (iv.clone(), aes128_cbc_encode(&self.plain, &self.key, &iv))
+        let mut iv = vec![0; self.block_size()];
+        rand::thread_rng().fill_bytes(&mut iv);
+        (
+            iv.clone(),
+            cbc_encode(&self.cipher, &self.plain, &iv).unwrap(),
+        )
     }
 
     pub fn has_valid_padding(&self, iv: &[u8], cipher: &[u8]) -> bool {
-        let plain = aes128_cbc_decode(cipher, &self.key, iv);
-        let padding_result = remove_pkcs7_padding(&plain);
-        padding_result.is_ok()
+        cbc_decode(&self.cipher, cipher, iv)
+            .and_then(|plain| remove_pkcs7_padding(&plain))
+            .is_ok()
     }
 }
 
-pub fn cbc_padding_attack(oracle: CBCPaddingOracle) -> Vec<u8> {
+pub fn cbc_padding_attack<C: BlockCipher>(oracle: CBCPaddingOracle<C>) -> Vec<u8> {
+    let block_size = oracle.block_size();
     let ciphertext = oracle.encrypt();
-    let blocks = ciphertext.1.len() / 16;
+    let blocks = ciphertext.1.len() / block_size;
     let mut result = Vec::new();
 
     for offset in (0..blocks).progress_with(create_progress_bar(blocks as u64)) {
+        let current = &ciphertext.1[offset * block_size..(offset + 1) * block_size];
         if offset == 0 {
             result.append(&mut cbc_padding_attack_blocks(
                 &oracle,
                 &ciphertext.0,
-                &ciphertext.1[offset * 16..(offset + 1) * 16],
+                current,
             ));
         } else {
             result.append(&mut cbc_padding_attack_blocks(
                 &oracle,
-                &ciphertext.1[(offset - 1) * 16..offset * 16],
-                &ciphertext.1[offset * 16..(offset + 1) * 16],
+                &ciphertext.1[(offset - 1) * block_size..offset * block_size],
+                current,
             ));
         }
     }
@@ -56,31 +75,32 @@ pub fn cbc_padding_attack(oracle: CBCPaddingOracle) -> Vec<u8> {
     result
 }
 
-fn cbc_padding_attack_blocks(
-    oracle: &CBCPaddingOracle,
+fn cbc_padding_attack_blocks<C: BlockCipher>(
+    oracle: &CBCPaddingOracle<C>,
     previous: &[u8],
     current: &[u8],
 ) -> Vec<u8> {
-    let mut zeroizing_iv = [0; 16];
+    let block_size = oracle.block_size();
+    let mut zeroizing_iv = vec![0; block_size];
 
-    for byte_number in (0..16).rev() {
-        let mut iv_for_this_byte = zeroizing_iv;
+    for byte_number in (0..block_size).rev() {
+        let mut iv_for_this_byte = zeroizing_iv.clone();
         for item in iv_for_this_byte.iter_mut().skip(byte_number + 1) {
-            *item ^= (16 - byte_number) as u8;
+            *item ^= (block_size - byte_number) as u8;
         }
         for i in 0..=255 {
-            let mut iv = iv_for_this_byte;
+            let mut iv = iv_for_this_byte.clone();
             iv[byte_number] ^= i;
             if oracle.has_valid_padding(&iv, current) {
                 if byte_number > 0 {
                     iv[byte_number - 1] ^= 128;
                     if oracle.has_valid_padding(&iv, current) {
-                        let plain_byte = i ^ (16 - byte_number as u8);
+                        let plain_byte = i ^ (block_size - byte_number) as u8;
                         zeroizing_iv[byte_number] = plain_byte;
                         continue;
                     }
                 } else {
-                    let plain_byte = i ^ (16 - byte_number as u8);
+                    let plain_byte = i ^ (block_size - byte_number) as u8;
                     zeroizing_iv[byte_number] = plain_byte;
                     continue;
                 }
@@ -94,7 +114,9 @@ fn cbc_padding_attack_blocks(
 mod tests {
     use std::fs;
 
-    use crate::util::base_64::Base64;
+    use aes::Aes192;
+
+    use crate::util::{base_64::Base64, generators::generate_aes_key, xtea::Xtea};
 
     use super::*;
 
@@ -112,4 +134,28 @@ mod tests {
             assert_eq!(output.lines().nth(i).unwrap().as_bytes(), plain)
         }
     }
+
+    #[test]
+    fn cbc_padding_attack_other_ciphers() {
+        let input = fs::read_to_string("data/set3/1.txt")
+            .expect("Something went wrong reading the challenge file");
+        let output = fs::read_to_string("data/set3/1_plain.txt")
+            .expect("Something went wrong reading the challenge file");
+        let secret = Base64::new_from_string(input.lines().next().unwrap()).unwrap();
+
+        let mut key = vec![0; 24];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        let oracle =
+            CBCPaddingOracle::with_cipher(Aes192::new_from_slice(&key).unwrap(), secret.to_bytes());
+        let res = cbc_padding_attack(oracle);
+        let plain = remove_pkcs7_padding(&res).unwrap();
+        assert_eq!(output.lines().next().unwrap().as_bytes(), plain);
+
+        let oracle =
+            CBCPaddingOracle::with_cipher(Xtea::new(&key[..16]).unwrap(), secret.to_bytes());
+        let res = cbc_padding_attack(oracle);
+        let plain = remove_pkcs7_padding(&res).unwrap();
+        assert_eq!(output.lines().next().unwrap().as_bytes(), plain);
+    }
 }
@@ -1,4 +1,7 @@
-use crate::s02::aes_cbc::{aes128_cbc_decode, aes128_cbc_encode};
+use crate::{
+    s02::aes_cbc::{aes128_cbc_decode, aes128_cbc_encode},
+    util::error::Result,
+};
 
 pub struct CBCOracle {
     key: Vec<u8>,
@@ -10,10 +13,10 @@ impl CBCOracle {
     }
 
     pub fn encrypt(&self, plain: &[u8]) -> Vec<u8> {
-        aes128_cbc_encode(plain, &self.key, &self.key)
+        aes128_cbc_encode(plain, &self.key, &self.key).unwrap()
     }
 
-    pub fn decrypt(&self, cipher: &[u8]) -> Vec<u8> {
+    pub fn decrypt(&self, cipher: &[u8]) -> Result<Vec<u8>> {
         aes128_cbc_decode(cipher, &self.key, &self.key)
     }
 }
@@ -40,7 +43,7 @@ mod tests {
 
         let tampered_cipher = [&cipher[0..16], &[0; 16], &cipher[0..16]].concat();
 
-        let tampered_plain = oracle.decrypt(&tampered_cipher);
+        let tampered_plain = oracle.decrypt(&tampered_cipher).unwrap();
 
         let recovered_key = xor(&tampered_plain[0..16], &tampered_plain[32..48]);
 
@@ -25,11 +25,16 @@ impl AdminOracleCTR {
         plain.append(&mut self.postfix.clone());
 
         let iv = generate_aes_key();
-        (iv.clone(), aes128_ctr_encode(&plain, &self.key, &iv))
+        (
+            iv.clone(),
+            aes128_ctr_encode(&plain, &self.key, &iv).unwrap(),
+        )
     }
 
     pub fn is_admin(&self, iv: &[u8], cipher: &[u8]) -> bool {
-        let plain = aes128_ctr_decode(cipher, &self.key, iv);
+        let Ok(plain) = aes128_ctr_decode(cipher, &self.key, iv) else {
+            return false;
+        };
         for substring in plain.split(|byte| *byte == b';') {
             if substring == b"admin=true" {
                 return true;
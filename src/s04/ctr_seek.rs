@@ -1,13 +1,27 @@
-use crate::s03::aes_ctr::{
-    aes128_ctr_decode_with_full_iv, aes128_ctr_encode_with_full_iv, increment_iv,
+use crate::{
+    s03::aes_ctr::{aes128_ctr_decode_with_full_iv, aes128_ctr_encode_with_full_iv, increment_iv},
+    util::error::{CryptoError, Result},
 };
 
-pub fn edit(cipher: &[u8], key: &[u8], iv: &[u8], offset: usize, newtext: &[u8]) -> Vec<u8> {
+pub fn edit(
+    cipher: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    offset: usize,
+    newtext: &[u8],
+) -> Result<Vec<u8>> {
     let block_offset = (offset / 16) * 16;
     let new_len = newtext.len();
     let mut res = cipher.to_vec();
 
     let iv_len = iv.len();
+    if iv_len > 16 {
+        return Err(CryptoError::InvalidIvLength(iv_len));
+    }
+    // edits can only overwrite existing ciphertext
+    if offset + new_len > cipher.len() {
+        return Err(CryptoError::InvalidCiphertextLength(cipher.len()));
+    }
 
     let mut iv: Vec<u8> = iv.to_vec();
     let mut append_zero = vec![0; 16 - iv_len];
@@ -20,12 +34,12 @@ pub fn edit(cipher: &[u8], key: &[u8], iv: &[u8], offset: usize, newtext: &[u8])
     let offset_pos = offset % 16;
     let offset_end = block_offset + offset_pos + new_len;
     let mut decrypted =
-        aes128_ctr_decode_with_full_iv(&cipher[block_offset..offset_end], key, &iv, iv_len);
+        aes128_ctr_decode_with_full_iv(&cipher[block_offset..offset_end], key, &iv, iv_len)?;
 
     decrypted.splice(offset_pos..offset_pos + new_len, newtext.to_vec());
-    let encrypted = aes128_ctr_encode_with_full_iv(&decrypted, key, &iv, iv_len);
+    let encrypted = aes128_ctr_encode_with_full_iv(&decrypted, key, &iv, iv_len)?;
     res.splice(block_offset..offset_end, encrypted);
-    res
+    Ok(res)
 }
 
 #[cfg(test)]
@@ -49,11 +63,11 @@ mod tests {
         let plain = fs::read_to_string("data/set1/7_plain.txt")
             .expect("Something went wrong reading the challenge file");
 
-        let cipher = aes128_ctr_encode(plain.as_bytes(), key, &iv);
+        let cipher = aes128_ctr_encode(plain.as_bytes(), key, &iv).unwrap();
 
-        let modified_encrypted = edit(&cipher, key, &iv, 5, "AAAAAA".as_bytes());
+        let modified_encrypted = edit(&cipher, key, &iv, 5, "AAAAAA".as_bytes()).unwrap();
 
-        let decrypted = aes128_ctr_decode(&modified_encrypted, key, &iv);
+        let decrypted = aes128_ctr_decode(&modified_encrypted, key, &iv).unwrap();
 
         let mut plain_bytes = plain.as_bytes().to_vec();
 
@@ -71,9 +85,11 @@ mod tests {
         let plain = fs::read_to_string("data/set1/7_plain.txt")
             .expect("Something went wrong reading the challenge file");
 
-        let cipher = aes128_ctr_encode(plain.as_bytes(), key, &iv);
+        let cipher = aes128_ctr_encode(plain.as_bytes(), key, &iv).unwrap();
+
+        let null_cipher = edit(&cipher, key, &iv, 0, &vec![0; cipher.len()]).unwrap();
 
-        let null_cipher = edit(&cipher, key, &iv, 0, &vec![0; cipher.len()]);
+        assert!(edit(&cipher, key, &iv, cipher.len(), b"A").is_err());
 
         let new_plain = xor(&cipher, &null_cipher);
 
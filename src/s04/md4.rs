@@ -1,6 +1,11 @@
 use std::convert::TryInto;
 
-use crate::util::bits::{u32_to_little_endian, u64_to_little_endian, u8_vector_to_u32_reverse};
+use crate::util::{
+    bits::{u32_to_little_endian, u8_vector_to_u32_reverse},
+    error::Result,
+};
+
+use super::merkle_damgard::{little_endian_padding, BlockBuffer};
 
 const A: u32 = 0x67452301;
 const B: u32 = 0xEFCDAB89;
@@ -9,10 +14,12 @@ const D: u32 = 0x10325476;
 const E: u32 = 0x5A827999;
 const F: u32 = 0x6ED9EBA1;
 
+pub const MD4_INITIAL_STATE: [u32; 4] = [A, B, C, D];
+
 pub fn md4(message: &[u8]) -> [u8; 16] {
-    let mut expanded_message = message.to_vec();
-    expanded_message.append(&mut get_padding(message.len(), message.len()));
-    md4_expanded(&expanded_message, A, B, C, D)
+    let mut hasher = MD4Hasher::new();
+    hasher.update(message);
+    hasher.finalize()
 }
 
 pub fn md4_unsafe_keyed_mac(key: &[u8], message: &[u8]) -> [u8; 16] {
@@ -25,134 +32,136 @@ pub fn extend_md4(
     keylen_bytes: usize,
     original_message_bytes: usize,
 ) -> (Vec<u8>, [u8; 16]) {
-    let glue_padding = get_padding(
-        keylen_bytes + original_message_bytes,
-        keylen_bytes + original_message_bytes,
-    );
-    let mut new_expanded_message = new_message.to_vec();
-    new_expanded_message.append(&mut get_padding(
-        new_message.len(),
-        keylen_bytes + original_message_bytes + glue_padding.len() + new_message.len(),
-    ));
-    (
-        glue_padding,
-        md4_expanded(
-            &new_expanded_message,
-            u8_vector_to_u32_reverse(&original_hash[0..4]),
-            u8_vector_to_u32_reverse(&original_hash[4..8]),
-            u8_vector_to_u32_reverse(&original_hash[8..12]),
-            u8_vector_to_u32_reverse(&original_hash[12..16]),
-        ),
-    )
-}
-
-fn md4_expanded(message: &[u8], a_in: u32, b_in: u32, c_in: u32, d_in: u32) -> [u8; 16] {
-    if message.len() % 64 != 0 {
-        panic!("Message len is not a multiple of 512 bit.");
+    let original_len = keylen_bytes + original_message_bytes;
+    let glue_padding = little_endian_padding(original_len);
+    let mut hasher =
+        MD4Hasher::from_state(original_hash, original_len + glue_padding.len()).unwrap();
+    hasher.update(new_message);
+    (glue_padding, hasher.finalize())
+}
+
+pub struct MD4Hasher {
+    state: [u32; 4],
+    buffer: BlockBuffer,
+}
+
+impl Default for MD4Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MD4Hasher {
+    pub fn new() -> MD4Hasher {
+        MD4Hasher {
+            state: MD4_INITIAL_STATE,
+            buffer: BlockBuffer::new(64),
+        }
     }
-    let mut a = a_in;
-    let mut b = b_in;
-    let mut c = c_in;
-    let mut d = d_in;
 
-    for chunk in message.chunks_exact(64) {
-        md4_chunk_loop(chunk, &mut a, &mut b, &mut c, &mut d);
+    pub fn from_state(hash: &[u8; 16], processed_len: usize) -> Result<MD4Hasher> {
+        Ok(MD4Hasher {
+            state: [0, 1, 2, 3].map(|i| u8_vector_to_u32_reverse(&hash[i * 4..i * 4 + 4])),
+            buffer: BlockBuffer::resume(64, processed_len)?,
+        })
     }
 
-    [
-        u32_to_little_endian(a),
-        u32_to_little_endian(b),
-        u32_to_little_endian(c),
-        u32_to_little_endian(d),
-    ]
-    .concat()
-    .try_into()
-    .unwrap()
+    pub fn update(&mut self, data: &[u8]) {
+        let [a, b, c, d] = &mut self.state;
+        self.buffer
+            .update(data, |chunk| md4_chunk_loop(chunk, a, b, c, d));
+    }
+
+    pub fn finalize(mut self) -> [u8; 16] {
+        let padding = little_endian_padding(self.buffer.length());
+        self.update(&padding);
+
+        self.state
+            .iter()
+            .flat_map(|word| u32_to_little_endian(*word))
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap()
+    }
 }
 
 fn md4_chunk_loop(chunk: &[u8], a: &mut u32, b: &mut u32, c: &mut u32, d: &mut u32) {
+    let states = md4_states([*a, *b, *c, *d], &md4_message_words(chunk));
+
+    *a = (*a).wrapping_add(states[48]);
+    *d = (*d).wrapping_add(states[49]);
+    *c = (*c).wrapping_add(states[50]);
+    *b = (*b).wrapping_add(states[51]);
+}
+
+pub fn md4_message_words(chunk: &[u8]) -> [u32; 16] {
     let mut x = [0; 16];
 
     for block in chunk.chunks_exact(4).enumerate() {
         x[block.0] = u8_vector_to_u32_reverse(block.1);
     }
 
-    let aa = *a;
-    let bb = *b;
-    let cc = *c;
-    let dd = *d;
-
-    //round 1
-    *a = ((*a).wrapping_add(f(*b, *c, *d)).wrapping_add(x[0])).rotate_left(3);
-    *d = ((*d).wrapping_add(f(*a, *b, *c)).wrapping_add(x[1])).rotate_left(7);
-    *c = ((*c).wrapping_add(f(*d, *a, *b)).wrapping_add(x[2])).rotate_left(11);
-    *b = ((*b).wrapping_add(f(*c, *d, *a)).wrapping_add(x[3])).rotate_left(19);
-    *a = ((*a).wrapping_add(f(*b, *c, *d)).wrapping_add(x[4])).rotate_left(3);
-    *d = ((*d).wrapping_add(f(*a, *b, *c)).wrapping_add(x[5])).rotate_left(7);
-    *c = ((*c).wrapping_add(f(*d, *a, *b)).wrapping_add(x[6])).rotate_left(11);
-    *b = ((*b).wrapping_add(f(*c, *d, *a)).wrapping_add(x[7])).rotate_left(19);
-    *a = ((*a).wrapping_add(f(*b, *c, *d)).wrapping_add(x[8])).rotate_left(3);
-    *d = ((*d).wrapping_add(f(*a, *b, *c)).wrapping_add(x[9])).rotate_left(7);
-    *c = ((*c).wrapping_add(f(*d, *a, *b)).wrapping_add(x[10])).rotate_left(11);
-    *b = ((*b).wrapping_add(f(*c, *d, *a)).wrapping_add(x[11])).rotate_left(19);
-    *a = ((*a).wrapping_add(f(*b, *c, *d)).wrapping_add(x[12])).rotate_left(3);
-    *d = ((*d).wrapping_add(f(*a, *b, *c)).wrapping_add(x[13])).rotate_left(7);
-    *c = ((*c).wrapping_add(f(*d, *a, *b)).wrapping_add(x[14])).rotate_left(11);
-    *b = ((*b).wrapping_add(f(*c, *d, *a)).wrapping_add(x[15])).rotate_left(19);
-
-    //round 2
-    *a = ((*a).wrapping_add(g(*b, *c, *d)).wrapping_add(x[0])).rotate_left(3);
-    *d = ((*d).wrapping_add(g(*a, *b, *c)).wrapping_add(x[4])).rotate_left(5);
-    *c = ((*c).wrapping_add(g(*d, *a, *b)).wrapping_add(x[8])).rotate_left(9);
-    *b = ((*b).wrapping_add(g(*c, *d, *a)).wrapping_add(x[12])).rotate_left(13);
-    *a = ((*a).wrapping_add(g(*b, *c, *d)).wrapping_add(x[1])).rotate_left(3);
-    *d = ((*d).wrapping_add(g(*a, *b, *c)).wrapping_add(x[5])).rotate_left(5);
-    *c = ((*c).wrapping_add(g(*d, *a, *b)).wrapping_add(x[9])).rotate_left(9);
-    *b = ((*b).wrapping_add(g(*c, *d, *a)).wrapping_add(x[13])).rotate_left(13);
-    *a = ((*a).wrapping_add(g(*b, *c, *d)).wrapping_add(x[2])).rotate_left(3);
-    *d = ((*d).wrapping_add(g(*a, *b, *c)).wrapping_add(x[6])).rotate_left(5);
-    *c = ((*c).wrapping_add(g(*d, *a, *b)).wrapping_add(x[10])).rotate_left(9);
-    *b = ((*b).wrapping_add(g(*c, *d, *a)).wrapping_add(x[14])).rotate_left(13);
-    *a = ((*a).wrapping_add(g(*b, *c, *d)).wrapping_add(x[3])).rotate_left(3);
-    *d = ((*d).wrapping_add(g(*a, *b, *c)).wrapping_add(x[7])).rotate_left(5);
-    *c = ((*c).wrapping_add(g(*d, *a, *b)).wrapping_add(x[11])).rotate_left(9);
-    *b = ((*b).wrapping_add(g(*c, *d, *a)).wrapping_add(x[15])).rotate_left(13);
-
-    //round 3
-    *a = ((*a).wrapping_add(h(*b, *c, *d)).wrapping_add(x[0])).rotate_left(3);
-    *d = ((*d).wrapping_add(h(*a, *b, *c)).wrapping_add(x[8])).rotate_left(9);
-    *c = ((*c).wrapping_add(h(*d, *a, *b)).wrapping_add(x[4])).rotate_left(11);
-    *b = ((*b).wrapping_add(h(*c, *d, *a)).wrapping_add(x[12])).rotate_left(15);
-    *a = ((*a).wrapping_add(h(*b, *c, *d)).wrapping_add(x[2])).rotate_left(3);
-    *d = ((*d).wrapping_add(h(*a, *b, *c)).wrapping_add(x[10])).rotate_left(9);
-    *c = ((*c).wrapping_add(h(*d, *a, *b)).wrapping_add(x[6])).rotate_left(11);
-    *b = ((*b).wrapping_add(h(*c, *d, *a)).wrapping_add(x[14])).rotate_left(15);
-    *a = ((*a).wrapping_add(h(*b, *c, *d)).wrapping_add(x[1])).rotate_left(3);
-    *d = ((*d).wrapping_add(h(*a, *b, *c)).wrapping_add(x[9])).rotate_left(9);
-    *c = ((*c).wrapping_add(h(*d, *a, *b)).wrapping_add(x[5])).rotate_left(11);
-    *b = ((*b).wrapping_add(h(*c, *d, *a)).wrapping_add(x[13])).rotate_left(15);
-    *a = ((*a).wrapping_add(h(*b, *c, *d)).wrapping_add(x[3])).rotate_left(3);
-    *d = ((*d).wrapping_add(h(*a, *b, *c)).wrapping_add(x[11])).rotate_left(9);
-    *c = ((*c).wrapping_add(h(*d, *a, *b)).wrapping_add(x[7])).rotate_left(11);
-    *b = ((*b).wrapping_add(h(*c, *d, *a)).wrapping_add(x[15])).rotate_left(15);
-
-    *a = (*a).wrapping_add(aa);
-    *b = (*b).wrapping_add(bb);
-    *c = (*c).wrapping_add(cc);
-    *d = (*d).wrapping_add(dd);
-}
-
-fn get_padding(true_message_length: usize, padding_message_length: usize) -> Vec<u8> {
-    let mut padding = Vec::<u8>::new();
-    padding.push(0x80);
-
-    while (padding.len() + true_message_length) % 64 != 56 {
-        padding.push(0x00);
+    x
+}
+
+pub fn md4_message_bytes(x: &[u32; 16]) -> Vec<u8> {
+    x.iter()
+        .flat_map(|word| u32_to_little_endian(*word))
+        .collect()
+}
+
+// Runs all 48 steps on the initial state (a, b, c, d). The result holds
+// a0, d0, c0, b0, a1, d1, c1, b1, ... in the order they are computed.
+pub fn md4_states(state: [u32; 4], x: &[u32; 16]) -> Vec<u32> {
+    let [a, b, c, d] = state;
+    let mut states = vec![a, d, c, b];
+    for step in 0..48 {
+        states.push(md4_step(&states, x, step));
+    }
+    states
+}
+
+// Computes states[step + 4] from the previous four states.
+pub fn md4_step(states: &[u32], x: &[u32; 16], step: usize) -> u32 {
+    states[step]
+        .wrapping_add(md4_step_function(states, step))
+        .wrapping_add(x[md4_step_word(step)])
+        .rotate_left(md4_step_shift(step))
+}
+
+// Returns the message word which makes step produce states[step + 4].
+pub fn md4_step_inverse(states: &[u32], step: usize) -> u32 {
+    states[step + 4]
+        .rotate_right(md4_step_shift(step))
+        .wrapping_sub(states[step])
+        .wrapping_sub(md4_step_function(states, step))
+}
+
+fn md4_step_function(states: &[u32], step: usize) -> u32 {
+    let b = states[step + 3];
+    let c = states[step + 2];
+    let d = states[step + 1];
+    match step / 16 {
+        0 => f(b, c, d),
+        1 => g(b, c, d),
+        _ => h(b, c, d),
     }
+}
 
-    padding.append(&mut u64_to_little_endian((padding_message_length * 8) as u64).into());
+pub fn md4_step_word(step: usize) -> usize {
+    match step / 16 {
+        0 => step,
+        1 => [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15][step % 16],
+        _ => [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15][step % 16],
+    }
+}
 
-    padding
+pub fn md4_step_shift(step: usize) -> u32 {
+    match step / 16 {
+        0 => [3, 7, 11, 19][step % 4],
+        1 => [3, 5, 9, 13][step % 4],
+        _ => [3, 9, 11, 15][step % 4],
+    }
 }
 
 // f(X,Y,Z)  =  XY v not(X)Z
@@ -192,4 +201,31 @@ mod tests {
 
         assert_eq!(hex::encode(md4_value2), "043f8582f241db351ce627e153e7f0e4");
     }
+
+    #[test]
+    fn md4_hasher() {
+        let message =
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+
+        for chunk_size in [1, 7, 63, 64, 65, 1000] {
+            let mut hasher = MD4Hasher::new();
+            for chunk in message.as_bytes().chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(
+                hex::encode(hasher.finalize()),
+                "e33b4ddc9c38f2199c3e7b164fcc0536"
+            );
+        }
+
+        // resuming from a digest hashes the padded message plus the new data
+        let hash = md4(message.as_bytes());
+        let padding = little_endian_padding(message.len());
+        let mut hasher = MD4Hasher::from_state(&hash, message.len() + padding.len()).unwrap();
+        hasher.update(b";admin=true");
+        assert_eq!(
+            hasher.finalize(),
+            md4(&[message.as_bytes(), &padding, b";admin=true"].concat())
+        );
+    }
 }
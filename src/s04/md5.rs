@@ -0,0 +1,239 @@
+use std::convert::TryInto;
+
+use crate::util::{
+    bits::{u32_to_little_endian, u8_vector_to_u32_reverse},
+    error::Result,
+};
+
+use super::{
+    md4::MD4_INITIAL_STATE,
+    merkle_damgard::{little_endian_padding, BlockBuffer},
+};
+
+// T[i] = floor(2^32 * abs(sin(i + 1)))
+const T: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+const SHIFTS: [[u32; 4]; 4] = [
+    [7, 12, 17, 22],
+    [5, 9, 14, 20],
+    [4, 11, 16, 23],
+    [6, 10, 15, 21],
+];
+
+// MD5 starts from the same state as MD4
+pub const MD5_INITIAL_STATE: [u32; 4] = MD4_INITIAL_STATE;
+
+pub fn md5(message: &[u8]) -> [u8; 16] {
+    let mut hasher = MD5Hasher::new();
+    hasher.update(message);
+    hasher.finalize()
+}
+
+pub fn md5_unsafe_keyed_mac(key: &[u8], message: &[u8]) -> [u8; 16] {
+    md5(&[key, message].concat())
+}
+
+pub fn extend_md5(
+    original_hash: &[u8; 16],
+    new_message: &[u8],
+    keylen_bytes: usize,
+    original_message_bytes: usize,
+) -> (Vec<u8>, [u8; 16]) {
+    let original_len = keylen_bytes + original_message_bytes;
+    let glue_padding = little_endian_padding(original_len);
+    let mut hasher =
+        MD5Hasher::from_state(original_hash, original_len + glue_padding.len()).unwrap();
+    hasher.update(new_message);
+    (glue_padding, hasher.finalize())
+}
+
+pub struct MD5Hasher {
+    state: [u32; 4],
+    buffer: BlockBuffer,
+}
+
+impl Default for MD5Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MD5Hasher {
+    pub fn new() -> MD5Hasher {
+        MD5Hasher {
+            state: MD5_INITIAL_STATE,
+            buffer: BlockBuffer::new(64),
+        }
+    }
+
+    pub fn from_state(hash: &[u8; 16], processed_len: usize) -> Result<MD5Hasher> {
+        Ok(MD5Hasher {
+            state: [0, 1, 2, 3].map(|i| u8_vector_to_u32_reverse(&hash[i * 4..i * 4 + 4])),
+            buffer: BlockBuffer::resume(64, processed_len)?,
+        })
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.buffer
+            .update(data, |chunk| md5_chunk_loop(chunk, &mut self.state));
+    }
+
+    pub fn finalize(mut self) -> [u8; 16] {
+        let padding = little_endian_padding(self.buffer.length());
+        self.update(&padding);
+
+        self.state
+            .iter()
+            .flat_map(|word| u32_to_little_endian(*word))
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap()
+    }
+}
+
+fn md5_chunk_loop(chunk: &[u8], state: &mut [u32; 4]) {
+    let mut x = [0; 16];
+
+    for block in chunk.chunks_exact(4).enumerate() {
+        x[block.0] = u8_vector_to_u32_reverse(block.1);
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+
+    for (step, t) in T.iter().enumerate() {
+        let round = step / 16;
+        let (f, k) = match round {
+            0 => (f(b, c, d), step),
+            1 => (g(b, c, d), (5 * step + 1) % 16),
+            2 => (h(b, c, d), (3 * step + 5) % 16),
+            _ => (i(b, c, d), (7 * step) % 16),
+        };
+        let temp = a
+            .wrapping_add(f)
+            .wrapping_add(x[k])
+            .wrapping_add(*t)
+            .rotate_left(SHIFTS[round][step % 4])
+            .wrapping_add(b);
+        a = d;
+        d = c;
+        c = b;
+        b = temp;
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+// F(X,Y,Z) = XY v not(X) Z
+fn f(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (!x & z)
+}
+
+// G(X,Y,Z) = XZ v Y not(Z)
+fn g(x: u32, y: u32, z: u32) -> u32 {
+    (x & z) | (y & !z)
+}
+
+// H(X,Y,Z) = X xor Y xor Z
+fn h(x: u32, y: u32, z: u32) -> u32 {
+    x ^ y ^ z
+}
+
+// I(X,Y,Z) = Y xor (X v not(Z))
+fn i(x: u32, y: u32, z: u32) -> u32 {
+    y ^ (x | !z)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::util::generators::generate_aes_key;
+
+    use super::*;
+
+    #[test]
+    fn md5_kat() {
+        // RFC 1321, appendix A.5
+        let vectors = [
+            ("", "d41d8cd98f00b204e9800998ecf8427e"),
+            ("a", "0cc175b9c0f1b6a831c399e269772661"),
+            ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+            ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+            (
+                "abcdefghijklmnopqrstuvwxyz",
+                "c3fcd3d76192e4007dfb496cca67e13b",
+            ),
+            (
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "d174ab98d277d9f5a5611c2c9f419d9f",
+            ),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+
+        for (message, digest) in vectors {
+            assert_eq!(hex::encode(md5(message.as_bytes())), digest);
+        }
+    }
+
+    #[test]
+    fn md5_hasher() {
+        let message =
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890";
+
+        for chunk_size in [1, 7, 63, 64, 65, 1000] {
+            let mut hasher = MD5Hasher::new();
+            for chunk in message.as_bytes().chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(
+                hex::encode(hasher.finalize()),
+                "57edf4a22be3c955ac49da2e2107b67a"
+            );
+        }
+
+        // resuming from a digest hashes the padded message plus the new data
+        let hash = md5(message.as_bytes());
+        let padding = little_endian_padding(message.len());
+        let mut hasher = MD5Hasher::from_state(&hash, message.len() + padding.len()).unwrap();
+        hasher.update(b";admin=true");
+        assert_eq!(
+            hasher.finalize(),
+            md5(&[message.as_bytes(), &padding, b";admin=true"].concat())
+        );
+    }
+
+    #[test]
+    fn md5_simple_length_extension() {
+        let key = generate_aes_key();
+        let message = "comment1=cooking MCs;userdata=foo;comment2= like a pound of bacon";
+
+        let mac = md5_unsafe_keyed_mac(&key, message.as_bytes());
+
+        let new_message_end = ";admin=true";
+
+        let (glue_padding, forged_hash) =
+            extend_md5(&mac, new_message_end.as_bytes(), 16, message.len());
+        let forged_message = [
+            message.as_bytes(),
+            &glue_padding,
+            new_message_end.as_bytes(),
+        ]
+        .concat();
+
+        assert_eq!(md5_unsafe_keyed_mac(&key, &forged_message), forged_hash);
+        assert_ne!(md5_unsafe_keyed_mac(&key, message.as_bytes()), forged_hash);
+    }
+}
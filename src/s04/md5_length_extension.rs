@@ -0,0 +1,79 @@
+use super::md5::{extend_md5, md5_unsafe_keyed_mac};
+
+pub struct MD5Oracle {
+    key: Vec<u8>,
+}
+
+impl MD5Oracle {
+    pub fn new(key: &[u8]) -> MD5Oracle {
+        MD5Oracle { key: key.to_vec() }
+    }
+
+    pub fn create_mac(&self, message: &[u8]) -> [u8; 16] {
+        md5_unsafe_keyed_mac(&self.key, message)
+    }
+
+    pub fn is_admin(&self, message: &[u8], mac: &[u8; 16]) -> bool {
+        let new_mac = md5_unsafe_keyed_mac(&self.key, message);
+        if new_mac != *mac {
+            return false;
+        }
+
+        for substring in message.split(|byte| *byte == b';') {
+            if substring == b"admin=true" {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+pub fn hack_md5_oracle(
+    oracle: &MD5Oracle,
+    old_message: &[u8],
+    old_hash: &[u8; 16],
+    new_message: &[u8],
+) -> Option<(Vec<u8>, [u8; 16])> {
+    for i in 0..=32 {
+        let (glue_padding, forged_hash) = extend_md5(old_hash, new_message, i, old_message.len());
+        let forged_message = [old_message, &glue_padding, new_message].concat();
+        if oracle.is_admin(&forged_message, &forged_hash) {
+            return Some((forged_message, forged_hash));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rand::{Rng, RngCore};
+
+    use super::*;
+
+    #[test]
+    fn md5_length_extension_attack() {
+        let keylen = rand::thread_rng().gen_range(0..32);
+        let mut key = vec![0; keylen];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        let oracle = MD5Oracle::new(&key);
+
+        let message = "comment1=cooking MCs;userdata=foo;comment2= like a pound of bacon";
+
+        let mac = oracle.create_mac(message.as_bytes());
+
+        let new_message_end = ";admin=true";
+
+        let (forged_message, forged_hash) = hack_md5_oracle(
+            &oracle,
+            message.as_bytes(),
+            &mac,
+            new_message_end.as_bytes(),
+        )
+        .unwrap();
+
+        assert!(oracle.is_admin(&forged_message, &forged_hash));
+    }
+}
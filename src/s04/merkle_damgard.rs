@@ -0,0 +1,124 @@
+use crate::util::{
+    bits::{u64_to_big_endian, u64_to_little_endian},
+    error::{CryptoError, Result},
+};
+
+// Collects the input of a hasher until there are whole blocks for its
+// compression function.
+pub(crate) struct BlockBuffer {
+    block_size: usize,
+    buffer: Vec<u8>,
+    length: usize,
+}
+
+impl BlockBuffer {
+    pub(crate) fn new(block_size: usize) -> BlockBuffer {
+        BlockBuffer {
+            block_size,
+            buffer: Vec::new(),
+            length: 0,
+        }
+    }
+
+    // Continues hashing after a digest. processed_len is the length of the
+    // message behind it including its padding, so it is a multiple of the
+    // block size.
+    pub(crate) fn resume(block_size: usize, processed_len: usize) -> Result<BlockBuffer> {
+        if !processed_len.is_multiple_of(block_size) {
+            return Err(CryptoError::InvalidMessageLength(processed_len));
+        }
+        Ok(BlockBuffer {
+            block_size,
+            buffer: Vec::new(),
+            length: processed_len,
+        })
+    }
+
+    pub(crate) fn length(&self) -> usize {
+        self.length
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8], mut compress: impl FnMut(&[u8])) {
+        self.length += data.len();
+        self.buffer.extend_from_slice(data);
+
+        let full_chunks = self.buffer.len() - self.buffer.len() % self.block_size;
+        for chunk in self.buffer[..full_chunks].chunks_exact(self.block_size) {
+            compress(chunk);
+        }
+        self.buffer.drain(..full_chunks);
+    }
+}
+
+// Padding of SHA-1 and SHA-256
+pub(crate) fn big_endian_padding(message_length: usize) -> Vec<u8> {
+    get_padding(
+        message_length,
+        64,
+        &u64_to_big_endian((message_length * 8) as u64),
+    )
+}
+
+// Padding of MD4 and MD5
+pub(crate) fn little_endian_padding(message_length: usize) -> Vec<u8> {
+    get_padding(
+        message_length,
+        64,
+        &u64_to_little_endian((message_length * 8) as u64),
+    )
+}
+
+// Padding of SHA-384 and SHA-512. The message length takes 128 bits, of
+// which the upper 64 are always zero here.
+pub(crate) fn sha512_padding(message_length: usize) -> Vec<u8> {
+    get_padding(
+        message_length,
+        128,
+        &[[0; 8], u64_to_big_endian((message_length * 8) as u64)].concat(),
+    )
+}
+
+fn get_padding(message_length: usize, block_size: usize, encoded_length: &[u8]) -> Vec<u8> {
+    let mut padding = Vec::<u8>::new();
+    padding.push(0x80);
+
+    while (padding.len() + message_length) % block_size != block_size - encoded_length.len() {
+        padding.push(0x00);
+    }
+
+    padding.extend_from_slice(encoded_length);
+
+    padding
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn padding_lengths() {
+        for message_length in [0, 1, 55, 56, 63, 64, 111, 112, 127, 128] {
+            assert!((message_length + big_endian_padding(message_length).len()).is_multiple_of(64));
+            assert!(
+                (message_length + little_endian_padding(message_length).len()).is_multiple_of(64)
+            );
+            assert!((message_length + sha512_padding(message_length).len()).is_multiple_of(128));
+        }
+
+        let padding = big_endian_padding(3);
+        assert_eq!(padding.len(), 61);
+        assert_eq!(padding[0], 0x80);
+        assert_eq!(padding[53..], [0, 0, 0, 0, 0, 0, 0, 24]);
+        assert_eq!(little_endian_padding(3)[53..], [24, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn resume_errors() {
+        assert_eq!(BlockBuffer::resume(64, 128).unwrap().length(), 128);
+        assert_eq!(
+            BlockBuffer::resume(64, 100).err(),
+            Some(CryptoError::InvalidMessageLength(100))
+        );
+    }
+}
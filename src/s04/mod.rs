@@ -4,6 +4,12 @@ pub mod ctr_seek;
 pub mod hmac;
 pub mod md4;
 pub mod md4_length_extension;
+pub mod md5;
+pub mod md5_length_extension;
+pub mod merkle_damgard;
 pub mod sha1;
 pub mod sha1_length_extension;
+pub mod sha2_length_extension;
+pub mod sha256;
+pub mod sha512;
 pub mod timing_oracle;
@@ -1,6 +1,11 @@
 use std::convert::TryInto;
 
-use crate::util::bits::{u32_to_big_endian, u64_to_big_endian, u8_vector_to_u32};
+use crate::util::{
+    bits::{u32_to_big_endian, u8_vector_to_u32},
+    error::Result,
+};
+
+use super::merkle_damgard::{big_endian_padding, BlockBuffer};
 
 const H0: u32 = 0x67452301;
 const H1: u32 = 0xEFCDAB89;
@@ -9,9 +14,9 @@ const H3: u32 = 0x10325476;
 const H4: u32 = 0xC3D2E1F0;
 
 pub fn sha1(message: &[u8]) -> [u8; 20] {
-    let mut expanded_message = message.to_vec();
-    expanded_message.append(&mut get_padding(message.len(), message.len()));
-    sha1_expanded_with_hs(&expanded_message, H0, H1, H2, H3, H4)
+    let mut hasher = SHA1Hasher::new();
+    hasher.update(message);
+    hasher.finalize()
 }
 
 pub fn sha1_unsafe_keyed_mac(key: &[u8], message: &[u8]) -> [u8; 20] {
@@ -24,59 +29,57 @@ pub fn extend_sha1(
     keylen_bytes: usize,
     original_message_bytes: usize,
 ) -> (Vec<u8>, [u8; 20]) {
-    let glue_padding = get_padding(
-        keylen_bytes + original_message_bytes,
-        keylen_bytes + original_message_bytes,
-    );
-    let mut new_expanded_message = new_message.to_vec();
-    new_expanded_message.append(&mut get_padding(
-        new_message.len(),
-        keylen_bytes + original_message_bytes + glue_padding.len() + new_message.len(),
-    ));
-    (
-        glue_padding,
-        sha1_expanded_with_hs(
-            &new_expanded_message,
-            u8_vector_to_u32(&original_hash[0..4]),
-            u8_vector_to_u32(&original_hash[4..8]),
-            u8_vector_to_u32(&original_hash[8..12]),
-            u8_vector_to_u32(&original_hash[12..16]),
-            u8_vector_to_u32(&original_hash[16..20]),
-        ),
-    )
+    let original_len = keylen_bytes + original_message_bytes;
+    let glue_padding = big_endian_padding(original_len);
+    let mut hasher =
+        SHA1Hasher::from_state(original_hash, original_len + glue_padding.len()).unwrap();
+    hasher.update(new_message);
+    (glue_padding, hasher.finalize())
+}
+
+pub struct SHA1Hasher {
+    h: [u32; 5],
+    buffer: BlockBuffer,
 }
 
-fn sha1_expanded_with_hs(
-    message: &[u8],
-    h0_in: u32,
-    h1_in: u32,
-    h2_in: u32,
-    h3_in: u32,
-    h4_in: u32,
-) -> [u8; 20] {
-    if message.len() % 64 != 0 {
-        panic!("Message len is not a multiple of 512 bit.");
+impl Default for SHA1Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SHA1Hasher {
+    pub fn new() -> SHA1Hasher {
+        SHA1Hasher {
+            h: [H0, H1, H2, H3, H4],
+            buffer: BlockBuffer::new(64),
+        }
+    }
+
+    pub fn from_state(hash: &[u8; 20], processed_len: usize) -> Result<SHA1Hasher> {
+        Ok(SHA1Hasher {
+            h: [0, 1, 2, 3, 4].map(|i| u8_vector_to_u32(&hash[i * 4..i * 4 + 4])),
+            buffer: BlockBuffer::resume(64, processed_len)?,
+        })
     }
-    let mut h0 = h0_in;
-    let mut h1 = h1_in;
-    let mut h2 = h2_in;
-    let mut h3 = h3_in;
-    let mut h4 = h4_in;
-
-    for chunk in message.chunks_exact(64) {
-        sha1_chunk_loop(chunk, &mut h0, &mut h1, &mut h2, &mut h3, &mut h4);
+
+    pub fn update(&mut self, data: &[u8]) {
+        let [h0, h1, h2, h3, h4] = &mut self.h;
+        self.buffer
+            .update(data, |chunk| sha1_chunk_loop(chunk, h0, h1, h2, h3, h4));
     }
 
-    [
-        u32_to_big_endian(h0),
-        u32_to_big_endian(h1),
-        u32_to_big_endian(h2),
-        u32_to_big_endian(h3),
-        u32_to_big_endian(h4),
-    ]
-    .concat()
-    .try_into()
-    .unwrap()
+    pub fn finalize(mut self) -> [u8; 20] {
+        let padding = big_endian_padding(self.buffer.length());
+        self.update(&padding);
+
+        self.h
+            .iter()
+            .flat_map(|h| u32_to_big_endian(*h))
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap()
+    }
 }
 
 fn sha1_chunk_loop(
@@ -148,19 +151,6 @@ fn sha1_chunk_loop(
     *h4 = (*h4).wrapping_add(e);
 }
 
-fn get_padding(true_message_length: usize, padding_message_length: usize) -> Vec<u8> {
-    let mut padding = Vec::<u8>::new();
-    padding.push(0x80);
-
-    while (padding.len() + true_message_length) % 64 != 56 {
-        padding.push(0x00);
-    }
-
-    padding.append(&mut u64_to_big_endian((padding_message_length * 8) as u64).into());
-
-    padding
-}
-
 #[cfg(test)]
 mod tests {
 
@@ -247,4 +237,32 @@ mod tests {
 
         assert!(is_admin);
     }
+
+    #[test]
+    fn sha1_hasher() {
+        let message = "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".repeat(5);
+
+        for chunk_size in [1, 7, 63, 64, 65, 1000] {
+            let mut hasher = SHA1Hasher::new();
+            for chunk in message.as_bytes().chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(
+                hex::encode(hasher.finalize()),
+                "b4f138e46b568a88464fa22be55a42013d2978c8"
+            );
+        }
+
+        // resuming from a digest hashes the padded message plus the new data
+        let hash = sha1(message.as_bytes());
+        let padding = big_endian_padding(message.len());
+        let mut hasher = SHA1Hasher::from_state(&hash, message.len() + padding.len()).unwrap();
+        hasher.update(b";admin=true");
+        assert_eq!(
+            hasher.finalize(),
+            sha1(&[message.as_bytes(), &padding, b";admin=true"].concat())
+        );
+
+        assert!(SHA1Hasher::from_state(&hash, message.len()).is_err());
+    }
 }
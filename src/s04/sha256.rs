@@ -0,0 +1,248 @@
+use std::convert::TryInto;
+
+use crate::util::{
+    bits::{u32_to_big_endian, u8_vector_to_u32},
+    error::Result,
+};
+
+use super::merkle_damgard::{big_endian_padding, BlockBuffer};
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA224_INITIAL_STATE: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
+
+pub fn sha256(message: &[u8]) -> [u8; 32] {
+    let mut hasher = SHA256Hasher::new();
+    hasher.update(message);
+    hasher.finalize()
+}
+
+// SHA-256 with a different initial state, truncated to 28 bytes
+pub fn sha224(message: &[u8]) -> [u8; 28] {
+    let mut hasher = SHA256Hasher::with_state(SHA224_INITIAL_STATE, BlockBuffer::new(64));
+    hasher.update(message);
+    hasher.finalize()[..28].try_into().unwrap()
+}
+
+pub fn sha256_unsafe_keyed_mac(key: &[u8], message: &[u8]) -> [u8; 32] {
+    sha256(&[key, message].concat())
+}
+
+pub fn sha224_unsafe_keyed_mac(key: &[u8], message: &[u8]) -> [u8; 28] {
+    sha224(&[key, message].concat())
+}
+
+pub fn extend_sha256(
+    original_hash: &[u8; 32],
+    new_message: &[u8],
+    keylen_bytes: usize,
+    original_message_bytes: usize,
+) -> (Vec<u8>, [u8; 32]) {
+    let original_len = keylen_bytes + original_message_bytes;
+    let glue_padding = big_endian_padding(original_len);
+    let mut hasher =
+        SHA256Hasher::from_state(original_hash, original_len + glue_padding.len()).unwrap();
+    hasher.update(new_message);
+    (glue_padding, hasher.finalize())
+}
+
+pub struct SHA256Hasher {
+    h: [u32; 8],
+    buffer: BlockBuffer,
+}
+
+impl Default for SHA256Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SHA256Hasher {
+    pub fn new() -> SHA256Hasher {
+        SHA256Hasher::with_state(SHA256_INITIAL_STATE, BlockBuffer::new(64))
+    }
+
+    pub fn from_state(hash: &[u8; 32], processed_len: usize) -> Result<SHA256Hasher> {
+        Ok(SHA256Hasher::with_state(
+            [0, 1, 2, 3, 4, 5, 6, 7].map(|i| u8_vector_to_u32(&hash[i * 4..i * 4 + 4])),
+            BlockBuffer::resume(64, processed_len)?,
+        ))
+    }
+
+    fn with_state(h: [u32; 8], buffer: BlockBuffer) -> SHA256Hasher {
+        SHA256Hasher { h, buffer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.buffer
+            .update(data, |chunk| sha256_chunk_loop(chunk, &mut self.h));
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let padding = big_endian_padding(self.buffer.length());
+        self.update(&padding);
+
+        self.h
+            .iter()
+            .flat_map(|h| u32_to_big_endian(*h))
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap()
+    }
+}
+
+fn sha256_chunk_loop(chunk: &[u8], h: &mut [u32; 8]) {
+    let mut w = [0; 64];
+
+    for block in chunk.chunks_exact(4).enumerate() {
+        w[block.0] = u8_vector_to_u32(block.1);
+    }
+
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+
+    for (item, k) in w.iter().zip(K) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ ((!e) & g);
+        let temp1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(k)
+            .wrapping_add(*item);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *state = state.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::util::generators::generate_aes_key;
+
+    use super::*;
+
+    #[test]
+    fn sha256_kat() {
+        // FIPS 180-4 examples
+        assert_eq!(
+            hex::encode(sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex::encode(sha256(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(
+            hex::encode(sha256(&vec![b'a'; 1000000])),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn sha224_kat() {
+        assert_eq!(
+            hex::encode(sha224(b"")),
+            "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f"
+        );
+        assert_eq!(
+            hex::encode(sha224(b"abc")),
+            "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"
+        );
+        assert_eq!(
+            hex::encode(sha224(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525"
+        );
+    }
+
+    #[test]
+    fn sha256_hasher() {
+        let message = "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".repeat(5);
+
+        for chunk_size in [1, 7, 63, 64, 65, 1000] {
+            let mut hasher = SHA256Hasher::new();
+            for chunk in message.as_bytes().chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(
+                hex::encode(hasher.finalize()),
+                "9ab6e0bfe43fc24162f8cc129d2b480fd6619b48019da77a5b8b05c02dff94d4"
+            );
+        }
+
+        // resuming from a digest hashes the padded message plus the new data
+        let hash = sha256(message.as_bytes());
+        let padding = big_endian_padding(message.len());
+        let mut hasher = SHA256Hasher::from_state(&hash, message.len() + padding.len()).unwrap();
+        hasher.update(b";admin=true");
+        assert_eq!(
+            hasher.finalize(),
+            sha256(&[message.as_bytes(), &padding, b";admin=true"].concat())
+        );
+    }
+
+    #[test]
+    fn sha256_simple_length_extension() {
+        let key = generate_aes_key();
+        let message = "comment1=cooking MCs;userdata=foo;comment2= like a pound of bacon";
+
+        let mac = sha256_unsafe_keyed_mac(&key, message.as_bytes());
+
+        let new_message_end = ";admin=true";
+
+        let (glue_padding, forged_hash) =
+            extend_sha256(&mac, new_message_end.as_bytes(), 16, message.len());
+        let forged_message = [
+            message.as_bytes(),
+            &glue_padding,
+            new_message_end.as_bytes(),
+        ]
+        .concat();
+
+        assert_eq!(sha256_unsafe_keyed_mac(&key, &forged_message), forged_hash);
+    }
+}
@@ -0,0 +1,182 @@
+use std::convert::TryInto;
+
+use super::{
+    sha256::{extend_sha256, sha224_unsafe_keyed_mac, sha256_unsafe_keyed_mac},
+    sha512::{extend_sha512, sha384_unsafe_keyed_mac, sha512_unsafe_keyed_mac},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SHA2 {
+    SHA224,
+    SHA256,
+    SHA384,
+    SHA512,
+}
+
+impl SHA2 {
+    pub fn unsafe_keyed_mac(&self, key: &[u8], message: &[u8]) -> Vec<u8> {
+        match self {
+            SHA2::SHA224 => sha224_unsafe_keyed_mac(key, message).to_vec(),
+            SHA2::SHA256 => sha256_unsafe_keyed_mac(key, message).to_vec(),
+            SHA2::SHA384 => sha384_unsafe_keyed_mac(key, message).to_vec(),
+            SHA2::SHA512 => sha512_unsafe_keyed_mac(key, message).to_vec(),
+        }
+    }
+
+    // Length extension needs the full state, which SHA-224 and SHA-384
+    // digests do not reveal.
+    pub fn extend(
+        &self,
+        original_hash: &[u8],
+        new_message: &[u8],
+        keylen_bytes: usize,
+        original_message_bytes: usize,
+    ) -> Option<(Vec<u8>, Vec<u8>)> {
+        let (glue_padding, hash) = match self {
+            SHA2::SHA224 | SHA2::SHA384 => return None,
+            SHA2::SHA256 => {
+                let (glue_padding, hash) = extend_sha256(
+                    original_hash.try_into().ok()?,
+                    new_message,
+                    keylen_bytes,
+                    original_message_bytes,
+                );
+                (glue_padding, hash.to_vec())
+            }
+            SHA2::SHA512 => {
+                let (glue_padding, hash) = extend_sha512(
+                    original_hash.try_into().ok()?,
+                    new_message,
+                    keylen_bytes,
+                    original_message_bytes,
+                );
+                (glue_padding, hash.to_vec())
+            }
+        };
+        Some((glue_padding, hash))
+    }
+}
+
+pub struct SHA2Oracle {
+    key: Vec<u8>,
+    hash: SHA2,
+}
+
+impl SHA2Oracle {
+    pub fn new(key: &[u8], hash: SHA2) -> SHA2Oracle {
+        SHA2Oracle {
+            key: key.to_vec(),
+            hash,
+        }
+    }
+
+    pub fn hash(&self) -> SHA2 {
+        self.hash
+    }
+
+    pub fn create_mac(&self, message: &[u8]) -> Vec<u8> {
+        self.hash.unsafe_keyed_mac(&self.key, message)
+    }
+
+    pub fn is_admin(&self, message: &[u8], mac: &[u8]) -> bool {
+        let new_mac = self.hash.unsafe_keyed_mac(&self.key, message);
+        if new_mac != mac {
+            return false;
+        }
+
+        for substring in message.split(|byte| *byte == b';') {
+            if substring == b"admin=true" {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+pub fn hack_sha2_oracle(
+    oracle: &SHA2Oracle,
+    old_message: &[u8],
+    old_hash: &[u8],
+    new_message: &[u8],
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    for i in 0..=32 {
+        let (glue_padding, forged_hash) =
+            oracle
+                .hash()
+                .extend(old_hash, new_message, i, old_message.len())?;
+        let forged_message = [old_message, &glue_padding, new_message].concat();
+        if oracle.is_admin(&forged_message, &forged_hash) {
+            return Some((forged_message, forged_hash));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rand::{Rng, RngCore};
+
+    use crate::util::generators::generate_aes_key;
+
+    use super::*;
+
+    const MESSAGE: &[u8] = b"comment1=cooking MCs;userdata=foo;comment2= like a pound of bacon";
+    const NEW_MESSAGE_END: &[u8] = b";admin=true";
+
+    #[test]
+    fn sha2_length_extension() {
+        for hash in [SHA2::SHA256, SHA2::SHA512] {
+            let keylen = rand::thread_rng().gen_range(0..32);
+            let mut key = vec![0; keylen];
+            rand::thread_rng().fill_bytes(&mut key);
+
+            let oracle = SHA2Oracle::new(&key, hash);
+            let mac = oracle.create_mac(MESSAGE);
+
+            let (forged_message, forged_hash) =
+                hack_sha2_oracle(&oracle, MESSAGE, &mac, NEW_MESSAGE_END).unwrap();
+            assert!(oracle.is_admin(&forged_message, &forged_hash));
+        }
+    }
+
+    #[test]
+    fn sha2_truncation_resists_length_extension() {
+        let key = generate_aes_key();
+
+        // the digests are shorter than the states the extension resumes from
+        let mac = sha224_unsafe_keyed_mac(&key, MESSAGE);
+        assert!(mac.len() < 32);
+        assert!(SHA2::SHA224
+            .extend(&mac, NEW_MESSAGE_END, key.len(), MESSAGE.len())
+            .is_none());
+
+        // even with the right key length, the missing state words are unknown
+        let mut state = [0; 32];
+        state[..28].copy_from_slice(&mac);
+        let (glue_padding, forged_hash) =
+            extend_sha256(&state, NEW_MESSAGE_END, key.len(), MESSAGE.len());
+        let forged_message = [MESSAGE, &glue_padding, NEW_MESSAGE_END].concat();
+        assert_ne!(
+            sha224_unsafe_keyed_mac(&key, &forged_message),
+            forged_hash[..28]
+        );
+
+        let mac = sha384_unsafe_keyed_mac(&key, MESSAGE);
+        assert!(mac.len() < 64);
+        let mut state = [0; 64];
+        state[..48].copy_from_slice(&mac);
+        let (glue_padding, forged_hash) =
+            extend_sha512(&state, NEW_MESSAGE_END, key.len(), MESSAGE.len());
+        let forged_message = [MESSAGE, &glue_padding, NEW_MESSAGE_END].concat();
+        assert_ne!(
+            sha384_unsafe_keyed_mac(&key, &forged_message),
+            forged_hash[..48]
+        );
+
+        let oracle = SHA2Oracle::new(&key, SHA2::SHA384);
+        let mac = oracle.create_mac(MESSAGE);
+        assert!(hack_sha2_oracle(&oracle, MESSAGE, &mac, NEW_MESSAGE_END).is_none());
+    }
+}
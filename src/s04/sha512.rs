@@ -0,0 +1,328 @@
+use std::convert::TryInto;
+
+use crate::util::{
+    bits::{u64_to_big_endian, u8_vector_to_u64},
+    error::Result,
+};
+
+use super::merkle_damgard::{sha512_padding, BlockBuffer};
+
+const K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+const SHA512_INITIAL_STATE: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SHA384_INITIAL_STATE: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+pub fn sha512(message: &[u8]) -> [u8; 64] {
+    let mut hasher = SHA512Hasher::new();
+    hasher.update(message);
+    hasher.finalize()
+}
+
+// SHA-512 with a different initial state, truncated to 48 bytes
+pub fn sha384(message: &[u8]) -> [u8; 48] {
+    let mut hasher = SHA512Hasher::with_state(SHA384_INITIAL_STATE, BlockBuffer::new(128));
+    hasher.update(message);
+    hasher.finalize()[..48].try_into().unwrap()
+}
+
+pub fn sha512_unsafe_keyed_mac(key: &[u8], message: &[u8]) -> [u8; 64] {
+    sha512(&[key, message].concat())
+}
+
+pub fn sha384_unsafe_keyed_mac(key: &[u8], message: &[u8]) -> [u8; 48] {
+    sha384(&[key, message].concat())
+}
+
+pub fn extend_sha512(
+    original_hash: &[u8; 64],
+    new_message: &[u8],
+    keylen_bytes: usize,
+    original_message_bytes: usize,
+) -> (Vec<u8>, [u8; 64]) {
+    let original_len = keylen_bytes + original_message_bytes;
+    let glue_padding = sha512_padding(original_len);
+    let mut hasher =
+        SHA512Hasher::from_state(original_hash, original_len + glue_padding.len()).unwrap();
+    hasher.update(new_message);
+    (glue_padding, hasher.finalize())
+}
+
+pub struct SHA512Hasher {
+    h: [u64; 8],
+    buffer: BlockBuffer,
+}
+
+impl Default for SHA512Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SHA512Hasher {
+    pub fn new() -> SHA512Hasher {
+        SHA512Hasher::with_state(SHA512_INITIAL_STATE, BlockBuffer::new(128))
+    }
+
+    pub fn from_state(hash: &[u8; 64], processed_len: usize) -> Result<SHA512Hasher> {
+        Ok(SHA512Hasher::with_state(
+            [0, 1, 2, 3, 4, 5, 6, 7].map(|i| u8_vector_to_u64(&hash[i * 8..i * 8 + 8])),
+            BlockBuffer::resume(128, processed_len)?,
+        ))
+    }
+
+    fn with_state(h: [u64; 8], buffer: BlockBuffer) -> SHA512Hasher {
+        SHA512Hasher { h, buffer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.buffer
+            .update(data, |chunk| sha512_chunk_loop(chunk, &mut self.h));
+    }
+
+    pub fn finalize(mut self) -> [u8; 64] {
+        let padding = sha512_padding(self.buffer.length());
+        self.update(&padding);
+
+        self.h
+            .iter()
+            .flat_map(|h| u64_to_big_endian(*h))
+            .collect::<Vec<u8>>()
+            .try_into()
+            .unwrap()
+    }
+}
+
+fn sha512_chunk_loop(chunk: &[u8], h: &mut [u64; 8]) {
+    let mut w = [0; 80];
+
+    for block in chunk.chunks_exact(8).enumerate() {
+        w[block.0] = u8_vector_to_u64(block.1);
+    }
+
+    for i in 16..80 {
+        let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+        let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+
+    for (item, k) in w.iter().zip(K) {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ ((!e) & g);
+        let temp1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(k)
+            .wrapping_add(*item);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+    }
+
+    for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *state = state.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::util::generators::generate_aes_key;
+
+    use super::*;
+
+    const TWO_BLOCKS: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    #[test]
+    fn sha512_kat() {
+        // FIPS 180-4 examples
+        assert_eq!(
+            hex::encode(sha512(b"")),
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"
+        );
+        assert_eq!(
+            hex::encode(sha512(b"abc")),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        assert_eq!(
+            hex::encode(sha512(TWO_BLOCKS)),
+            "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"
+        );
+    }
+
+    #[test]
+    fn sha384_kat() {
+        assert_eq!(
+            hex::encode(sha384(b"")),
+            "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b"
+        );
+        assert_eq!(
+            hex::encode(sha384(b"abc")),
+            "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"
+        );
+        assert_eq!(
+            hex::encode(sha384(TWO_BLOCKS)),
+            "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039"
+        );
+    }
+
+    #[test]
+    fn sha512_hasher() {
+        let message = TWO_BLOCKS.repeat(3);
+
+        for chunk_size in [1, 7, 127, 128, 129, 1000] {
+            let mut hasher = SHA512Hasher::new();
+            for chunk in message.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(
+                hex::encode(hasher.finalize()),
+                "6e59d86c93e5aee5e08c8d6ca7b84f8f47fec3fce309d18e50acd71bfac857038ccea47330191965f3ec37eaa5e45f67356f3c32475bb1525b12a43dc24036b9"
+            );
+        }
+
+        // resuming from a digest hashes the padded message plus the new data
+        let hash = sha512(&message);
+        let padding = sha512_padding(message.len());
+        let mut hasher = SHA512Hasher::from_state(&hash, message.len() + padding.len()).unwrap();
+        hasher.update(b";admin=true");
+        assert_eq!(
+            hasher.finalize(),
+            sha512(&[&message, &padding, b";admin=true".as_slice()].concat())
+        );
+    }
+
+    #[test]
+    fn sha512_simple_length_extension() {
+        let key = generate_aes_key();
+        let message = "comment1=cooking MCs;userdata=foo;comment2= like a pound of bacon";
+
+        let mac = sha512_unsafe_keyed_mac(&key, message.as_bytes());
+
+        let new_message_end = ";admin=true";
+
+        let (glue_padding, forged_hash) =
+            extend_sha512(&mac, new_message_end.as_bytes(), 16, message.len());
+        let forged_message = [
+            message.as_bytes(),
+            &glue_padding,
+            new_message_end.as_bytes(),
+        ]
+        .concat();
+
+        assert_eq!(sha512_unsafe_keyed_mac(&key, &forged_message), forged_hash);
+    }
+}
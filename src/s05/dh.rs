@@ -13,6 +13,25 @@ pub fn get_nist_g() -> Integer {
     Integer::from_str_radix("2", 16).unwrap()
 }
 
+// p - 1 = q * j, where j has many small factors
+pub fn get_smooth_p() -> Integer {
+    Integer::from_str_radix(
+        "7199773997391911030609999317773941274322764333428698921736339643928346453700085358802973900485592910475480089726140708102474957429903531369589969318716771",
+        10,
+    ).unwrap()
+}
+
+pub fn get_smooth_g() -> Integer {
+    Integer::from_str_radix(
+        "4565356397095740655436854503483826832136106141639563487732438195343690437606117828318042418238184896212352329118608100083187535033402010599512641674644143",
+        10,
+    ).unwrap()
+}
+
+pub fn get_smooth_q() -> Integer {
+    Integer::from_str_radix("236234353446506858198510045061214171961", 10).unwrap()
+}
+
 pub fn generate_dh_key(p: &Integer, g: &Integer) -> (Integer, Integer) {
     let low = Integer::from(1);
     let high = p - Integer::from(1);
@@ -58,4 +77,14 @@ mod tests {
 
         //println!("{}", s_for_a.to_str_radix(16));
     }
+
+    #[test]
+    fn smooth_group() {
+        let p = get_smooth_p();
+        let g = get_smooth_g();
+        let q = get_smooth_q();
+
+        assert_eq!(Integer::from(&p - 1) % &q, 0);
+        assert_eq!(g.pow_mod(&q, &p).unwrap(), 1);
+    }
 }
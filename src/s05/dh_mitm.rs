@@ -8,7 +8,7 @@ use crate::{
         padding::remove_pkcs7_padding,
     },
     s04::sha1::sha1,
-    util::generators::generate_aes_key,
+    util::{error::Result, generators::generate_aes_key},
 };
 
 use super::dh::*;
@@ -53,12 +53,12 @@ impl Participant {
         let iv = generate_aes_key();
         (
             iv.clone(),
-            aes128_cbc_encode(plain, &self.session_key.unwrap(), &iv),
+            aes128_cbc_encode(plain, &self.session_key.unwrap(), &iv).unwrap(),
         )
     }
-    pub fn decrypt_message(&self, iv: &[u8], cipher: &[u8]) -> Vec<u8> {
-        let out = aes128_cbc_decode(cipher, &self.session_key.unwrap(), iv);
-        remove_pkcs7_padding(&out).expect("invalid padding")
+    pub fn decrypt_message(&self, iv: &[u8], cipher: &[u8]) -> Result<Vec<u8>> {
+        let out = aes128_cbc_decode(cipher, &self.session_key.unwrap(), iv)?;
+        remove_pkcs7_padding(&out)
     }
 }
 
@@ -87,7 +87,7 @@ mod tests {
         bob.receive_first_message(&a_a);
 
         let (iv, cipher) = alice.encrypt_message(message.as_bytes());
-        let decrypted = bob.decrypt_message(&iv, &cipher);
+        let decrypted = bob.decrypt_message(&iv, &cipher).unwrap();
 
         assert_eq!(message, std::str::from_utf8(&decrypted).unwrap());
     }
@@ -105,13 +105,13 @@ mod tests {
         bob.receive_first_message(&get_nist_p());
 
         let (iv, cipher) = alice.encrypt_message(message.as_bytes());
-        let decrypted = bob.decrypt_message(&iv, &cipher);
+        let decrypted = bob.decrypt_message(&iv, &cipher).unwrap();
 
         assert_eq!(message, std::str::from_utf8(&decrypted).unwrap());
 
         let session_key = get_session_key_for(Integer::from(0));
 
-        let out = aes128_cbc_decode(&cipher, &session_key, &iv);
+        let out = aes128_cbc_decode(&cipher, &session_key, &iv).unwrap();
         let decrypted_mal = remove_pkcs7_padding(&out).expect("invalid padding");
 
         assert_eq!(message, std::str::from_utf8(&decrypted_mal).unwrap())
@@ -130,13 +130,13 @@ mod tests {
         bob.receive_first_message(&a_a);
 
         let (iv, cipher) = alice.encrypt_message(message.as_bytes());
-        let decrypted = bob.decrypt_message(&iv, &cipher);
+        let decrypted = bob.decrypt_message(&iv, &cipher).unwrap();
 
         assert_eq!(message, std::str::from_utf8(&decrypted).unwrap());
 
         let session_key = get_session_key_for(Integer::from(1));
 
-        let out = aes128_cbc_decode(&cipher, &session_key, &iv);
+        let out = aes128_cbc_decode(&cipher, &session_key, &iv).unwrap();
         let decrypted_mal = remove_pkcs7_padding(&out).expect("invalid padding");
 
         assert_eq!(message, std::str::from_utf8(&decrypted_mal).unwrap())
@@ -155,13 +155,13 @@ mod tests {
         bob.receive_first_message(&a_a);
 
         let (iv, cipher) = alice.encrypt_message(message.as_bytes());
-        let decrypted = bob.decrypt_message(&iv, &cipher);
+        let decrypted = bob.decrypt_message(&iv, &cipher).unwrap();
 
         assert_eq!(message, std::str::from_utf8(&decrypted).unwrap());
 
         let session_key = get_session_key_for(Integer::from(0));
 
-        let out = aes128_cbc_decode(&cipher, &session_key, &iv);
+        let out = aes128_cbc_decode(&cipher, &session_key, &iv).unwrap();
         let decrypted_mal = remove_pkcs7_padding(&out).expect("invalid padding");
 
         assert_eq!(message, std::str::from_utf8(&decrypted_mal).unwrap())
@@ -182,7 +182,7 @@ mod tests {
         bob.receive_first_message(&a_a);
 
         let (iv, cipher) = alice.encrypt_message(message.as_bytes());
-        let decrypted = bob.decrypt_message(&iv, &cipher);
+        let decrypted = bob.decrypt_message(&iv, &cipher).unwrap();
 
         assert_eq!(message, std::str::from_utf8(&decrypted).unwrap());
 
@@ -192,7 +192,7 @@ mod tests {
             get_session_key_for(p_minus_one)
         };
 
-        let out = aes128_cbc_decode(&cipher, &session_key, &iv);
+        let out = aes128_cbc_decode(&cipher, &session_key, &iv).unwrap();
         let decrypted_mal = remove_pkcs7_padding(&out).expect("invalid padding");
 
         assert_eq!(message, std::str::from_utf8(&decrypted_mal).unwrap())
@@ -26,6 +26,21 @@ pub struct RsaPrivate {
 }
 
 impl RsaPrivate {
+    pub fn new(d: &Integer, n: &Integer) -> RsaPrivate {
+        RsaPrivate {
+            d: d.clone(),
+            n: n.clone(),
+        }
+    }
+
+    pub fn d(&self) -> &Integer {
+        &self.d
+    }
+
+    pub fn n(&self) -> &Integer {
+        &self.n
+    }
+
     pub fn decrypt(&self, c: &rug::Integer) -> rug::Integer {
         c.clone().pow_mod(&self.d, &self.n).unwrap()
     }
@@ -46,7 +61,6 @@ pub fn rsa_keygen(bits: usize) -> (RsaPublic, RsaPrivate) {
         let mut q = generate_prime(bits / 2);
         while p == q {
             q = generate_prime(bits / 2);
-            println!("p = {p}, q = {q}");
         }
 
         n = Integer::from(&p * &q);
@@ -38,6 +38,7 @@ impl DsaParameters {
     }
 }
 
+#[derive(Clone)]
 pub struct Signature {
     pub r: Integer,
     pub s: Integer,
@@ -3,4 +3,5 @@ pub mod dsa_bad_parameters;
 pub mod dsa_repeated_nonce;
 pub mod pkcs1_5;
 pub mod rsa_parity;
+pub mod rsa_signature;
 pub mod unpadded_oracle;
@@ -0,0 +1,129 @@
+use rug::Integer;
+
+use crate::{
+    s04::sha1::sha1,
+    s05::rsa::{RsaPrivate, RsaPublic},
+    util::integer::{from_bytes, to_bytes},
+};
+
+// DER encoding of DigestInfo { AlgorithmIdentifier { sha1, NULL }, OCTET STRING (20 bytes) }
+const SHA1_DIGEST_INFO: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+
+fn digest_info(message: &[u8]) -> Vec<u8> {
+    [&SHA1_DIGEST_INFO[..], &sha1(message)].concat()
+}
+
+// EM = 00 01 FF .. FF 00 || DigestInfo
+pub fn encode_signature(message: &[u8], k: usize) -> Option<Vec<u8>> {
+    let t = digest_info(message);
+    if k < t.len() + 11 {
+        return None;
+    }
+    let mut encoded = vec![0xff; k];
+    encoded[0] = 0;
+    encoded[1] = 1;
+    encoded[k - t.len() - 1] = 0;
+    encoded[k - t.len()..].copy_from_slice(&t);
+    Some(encoded)
+}
+
+impl RsaPrivate {
+    pub fn sign_pkcs1_5(&self, message: &[u8]) -> Option<Integer> {
+        let k = self.n.significant_digits::<u8>();
+        let encoded = encode_signature(message, k)?;
+        Some(self.decrypt(&from_bytes(&encoded)))
+    }
+}
+
+impl RsaPublic {
+    fn recover_encoded(&self, signature: &Integer) -> Vec<u8> {
+        let k = self.n.significant_digits::<u8>();
+        let decrypted = to_bytes(&self.encrypt(signature));
+        let mut encoded = vec![0; k.saturating_sub(decrypted.len())];
+        encoded.extend_from_slice(&decrypted);
+        encoded
+    }
+
+    pub fn verify_pkcs1_5(&self, message: &[u8], signature: &Integer) -> bool {
+        let k = self.n.significant_digits::<u8>();
+        match encode_signature(message, k) {
+            Some(expected) => self.recover_encoded(signature) == expected,
+            None => false,
+        }
+    }
+
+    // Parses the padding from the left and never checks that the hash is
+    // right-aligned, so arbitrary bytes may follow it.
+    pub fn verify_pkcs1_5_sloppy(&self, message: &[u8], signature: &Integer) -> bool {
+        let encoded = self.recover_encoded(signature);
+        if encoded.len() < 3 || encoded[0] != 0 || encoded[1] != 1 {
+            return false;
+        }
+
+        let padding_len = encoded[2..]
+            .iter()
+            .take_while(|byte| **byte == 0xff)
+            .count();
+        let rest = &encoded[2 + padding_len..];
+        if padding_len == 0 || rest.first() != Some(&0) {
+            return false;
+        }
+
+        rest[1..].starts_with(&digest_info(message))
+    }
+}
+
+// Bleichenbacher's e = 3 forgery: 00 01 FF 00 || DigestInfo || garbage is a
+// perfect cube for the right garbage, so the cube root needs no private key.
+pub fn forge_signature(public_key: &RsaPublic, message: &[u8]) -> Option<Integer> {
+    if public_key.e != 3 {
+        return None;
+    }
+    let k = public_key.n.significant_digits::<u8>();
+    let prefix = [&[0x00, 0x01, 0xff, 0x00][..], &digest_info(message)].concat();
+    if k < prefix.len() {
+        return None;
+    }
+
+    let mut encoded = prefix;
+    encoded.resize(k, 0xff);
+    let forged = from_bytes(&encoded).root(3);
+
+    if public_key.verify_pkcs1_5_sloppy(message, &forged) {
+        Some(forged)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::s05::rsa::rsa_keygen;
+
+    use super::*;
+
+    #[test]
+    fn pkcs1_5_signature() {
+        let (public_key, private_key) = rsa_keygen(1024);
+        let message = b"hi mom";
+
+        let signature = private_key.sign_pkcs1_5(message).unwrap();
+        assert!(public_key.verify_pkcs1_5(message, &signature));
+        assert!(public_key.verify_pkcs1_5_sloppy(message, &signature));
+        assert!(!public_key.verify_pkcs1_5(b"hi dad", &signature));
+        assert!(!public_key.verify_pkcs1_5_sloppy(b"hi dad", &signature));
+    }
+
+    #[test]
+    fn s06e02() {
+        let (public_key, _) = rsa_keygen(1024);
+        let message = b"hi mom";
+
+        let forged = forge_signature(&public_key, message).unwrap();
+        assert!(public_key.verify_pkcs1_5_sloppy(message, &forged));
+        assert!(!public_key.verify_pkcs1_5(message, &forged));
+    }
+}
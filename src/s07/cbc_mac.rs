@@ -0,0 +1,262 @@
+use std::str::from_utf8;
+
+use crate::{
+    s02::{aes_cbc::aes128_cbc_encode, padding::pkcs7_padding},
+    util::{error::Result, generators::generate_aes_key, xor::xor},
+};
+
+pub fn cbc_mac(message: &[u8], key: &[u8], iv: &[u8]) -> Result<[u8; 16]> {
+    let cipher = aes128_cbc_encode(message, key, iv)?;
+    Ok(cipher[cipher.len() - 16..].try_into().unwrap())
+}
+
+pub fn cbc_mac_padding(message_len: usize) -> Vec<u8> {
+    pkcs7_padding(&[], 16 - message_len % 16).unwrap()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: u32,
+    pub to: u32,
+    pub amount: u64,
+}
+
+pub struct BankServer {
+    key: Vec<u8>,
+}
+
+impl BankServer {
+    pub fn new(key: &[u8]) -> BankServer {
+        BankServer { key: key.to_vec() }
+    }
+
+    // request = message || iv || mac
+    pub fn process_request(&self, request: &[u8]) -> Option<Transfer> {
+        if request.len() < 32 {
+            return None;
+        }
+        let (message, iv_mac) = request.split_at(request.len() - 32);
+        let (iv, mac) = iv_mac.split_at(16);
+        if cbc_mac(message, &self.key, iv).ok()? != mac {
+            return None;
+        }
+
+        let mut from = None;
+        let mut to = None;
+        let mut amount = None;
+        for pair in message.split(|byte| *byte == b'&') {
+            let pair = from_utf8(pair).ok()?;
+            match pair.split_once('=') {
+                Some(("from", value)) => from = value.parse().ok(),
+                Some(("to", value)) => to = value.parse().ok(),
+                Some(("amount", value)) => amount = value.parse().ok(),
+                _ => return None,
+            }
+        }
+
+        Some(Transfer {
+            from: from?,
+            to: to?,
+            amount: amount?,
+        })
+    }
+
+    // request = message || mac, the iv is fixed to zero
+    pub fn process_multi_request(&self, request: &[u8]) -> Option<Vec<Transfer>> {
+        if request.len() < 16 {
+            return None;
+        }
+        let (message, mac) = request.split_at(request.len() - 16);
+        if cbc_mac(message, &self.key, &[0; 16]).ok()? != mac {
+            return None;
+        }
+
+        let from_prefix = b"from=";
+        let list_separator = b"&tx_list=";
+        if !message.starts_with(from_prefix) {
+            return None;
+        }
+        let list_start = message
+            .windows(list_separator.len())
+            .position(|window| window == list_separator)?;
+        let from = from_utf8(&message[from_prefix.len()..list_start])
+            .ok()?
+            .parse()
+            .ok()?;
+
+        // malformed transactions are skipped
+        let transfers = message[list_start + list_separator.len()..]
+            .split(|byte| *byte == b';')
+            .filter_map(|transaction| {
+                let (to, amount) = from_utf8(transaction).ok()?.split_once(':')?;
+                Some(Transfer {
+                    from,
+                    to: to.parse().ok()?,
+                    amount: amount.parse().ok()?,
+                })
+            })
+            .collect();
+
+        Some(transfers)
+    }
+}
+
+pub struct BankClient {
+    key: Vec<u8>,
+    account: u32,
+}
+
+impl BankClient {
+    pub fn new(key: &[u8], account: u32) -> BankClient {
+        BankClient {
+            key: key.to_vec(),
+            account,
+        }
+    }
+
+    pub fn transfer_request(&self, to: u32, amount: u64) -> Vec<u8> {
+        let message = format!("from={}&to={to}&amount={amount}", self.account);
+        let iv = generate_aes_key();
+        let mac = cbc_mac(message.as_bytes(), &self.key, &iv).unwrap();
+        [message.as_bytes(), &iv, &mac].concat()
+    }
+
+    pub fn multi_transfer_request(&self, transactions: &[(u32, u64)]) -> Vec<u8> {
+        let list: Vec<String> = transactions
+            .iter()
+            .map(|(to, amount)| format!("{to}:{amount}"))
+            .collect();
+        let message = format!("from={}&tx_list={}", self.account, list.join(";"));
+        let mac = cbc_mac(message.as_bytes(), &self.key, &[0; 16]).unwrap();
+        [message.as_bytes(), &mac].concat()
+    }
+}
+
+pub fn forge_transfer_with_iv(own_request: &[u8], own_account: u32, victim: u32) -> Vec<u8> {
+    let own_from = format!("from={own_account}&");
+    let victim_from = format!("from={victim}&");
+    if own_from.len() != victim_from.len() || own_from.len() > 16 {
+        panic!("account numbers must have the same length");
+    }
+
+    let (message, iv_mac) = own_request.split_at(own_request.len() - 32);
+    let (iv, mac) = iv_mac.split_at(16);
+    if !message.starts_with(own_from.as_bytes()) {
+        panic!("request does not originate from our own account");
+    }
+
+    let mut forged_message = message.to_vec();
+    forged_message[..victim_from.len()].copy_from_slice(victim_from.as_bytes());
+
+    let mut forged_iv = iv.to_vec();
+    let difference = xor(own_from.as_bytes(), victim_from.as_bytes());
+    for (byte, diff) in forged_iv.iter_mut().zip(difference) {
+        *byte ^= diff;
+    }
+
+    [&forged_message, &forged_iv[..], mac].concat()
+}
+
+// Our own message needs at least one full block to absorb the victim's MAC.
+pub fn forge_multi_transfer(victim_request: &[u8], own_request: &[u8]) -> Option<Vec<u8>> {
+    if victim_request.len() < 16 || own_request.len() < 32 {
+        return None;
+    }
+    let (victim_message, victim_mac) = victim_request.split_at(victim_request.len() - 16);
+    let (own_message, own_mac) = own_request.split_at(own_request.len() - 16);
+
+    // after the padded victim message the CBC state is the victim's MAC, so
+    // xoring it into our first block continues exactly like our own request
+    let glued_block = xor(&own_message[..16], victim_mac);
+
+    Some(
+        [
+            victim_message,
+            &cbc_mac_padding(victim_message.len()),
+            &glued_block,
+            &own_message[16..],
+            own_mac,
+        ]
+        .concat(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn cbc_mac_test() {
+        let key = generate_aes_key();
+        let iv = generate_aes_key();
+        let mac = cbc_mac(b"from=1&to=2&amount=3", &key, &iv).unwrap();
+        assert_ne!(Ok(mac), cbc_mac(b"from=1&to=2&amount=4", &key, &iv));
+        assert_ne!(Ok(mac), cbc_mac(b"from=1&to=2&amount=3", &key, &[0; 16]));
+        assert_eq!(cbc_mac_padding(16), [16; 16]);
+        assert_eq!(cbc_mac_padding(13), [3; 3]);
+    }
+
+    #[test]
+    fn s07e01_iv() {
+        let key = generate_aes_key();
+        let server = BankServer::new(&key);
+
+        let attacker = 3;
+        let victim = 7;
+        let attacker_client = BankClient::new(&key, attacker);
+
+        let request = attacker_client.transfer_request(attacker, 1000000);
+        assert_eq!(
+            server.process_request(&request).unwrap(),
+            Transfer {
+                from: attacker,
+                to: attacker,
+                amount: 1000000
+            }
+        );
+
+        let forged = forge_transfer_with_iv(&request, attacker, victim);
+        assert_eq!(
+            server.process_request(&forged).unwrap(),
+            Transfer {
+                from: victim,
+                to: attacker,
+                amount: 1000000
+            }
+        );
+    }
+
+    #[test]
+    fn s07e01_length_extension() {
+        let key = generate_aes_key();
+        let server = BankServer::new(&key);
+
+        let attacker = 3;
+        let victim = 7;
+        let victim_client = BankClient::new(&key, victim);
+        let attacker_client = BankClient::new(&key, attacker);
+
+        let victim_request = victim_client.multi_transfer_request(&[(5, 100), (8, 250)]);
+        assert_eq!(
+            server.process_multi_request(&victim_request).unwrap().len(),
+            2
+        );
+
+        let own_request =
+            attacker_client.multi_transfer_request(&[(attacker, 1), (attacker, 1000000)]);
+
+        let forged = forge_multi_transfer(&victim_request, &own_request).unwrap();
+        let transfers = server.process_multi_request(&forged).unwrap();
+
+        assert!(transfers.contains(&Transfer {
+            from: victim,
+            to: attacker,
+            amount: 1000000
+        }));
+
+        let short_request = [b"from=3&tx".as_slice(), &[0; 16]].concat();
+        assert_eq!(forge_multi_transfer(&victim_request, &short_request), None);
+        assert_eq!(forge_multi_transfer(&[0; 8], &own_request), None);
+    }
+}
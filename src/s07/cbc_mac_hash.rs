@@ -0,0 +1,66 @@
+use rand::Rng;
+
+use crate::{s02::aes_cbc::aes128_cbc_encode, util::xor::xor};
+
+use super::cbc_mac::cbc_mac;
+
+pub fn cbc_mac_hash(message: &[u8]) -> [u8; 16] {
+    cbc_mac(message, b"YELLOW SUBMARINE", &[0; 16]).unwrap()
+}
+
+// CBC state after processing the given full blocks without padding
+fn cbc_state(message: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+    let cipher = aes128_cbc_encode(message, key, iv).unwrap();
+    cipher[message.len() - 16..message.len()].to_vec()
+}
+
+pub fn forge_snippet(original: &[u8], new_code: &[u8], key: &[u8], iv: &[u8]) -> Vec<u8> {
+    if original.len() < 16 {
+        panic!("original snippet must be at least one block long");
+    }
+    let mut rng = rand::thread_rng();
+
+    // everything from the comment start up to the next newline is ignored,
+    // so the glue block and the rest of the original line must not contain one
+    let mut prefix = new_code.to_vec();
+    prefix.extend_from_slice(b"//");
+    let filler_len = 16 - prefix.len() % 16;
+
+    loop {
+        let mut candidate = prefix.clone();
+        candidate.extend((0..filler_len).map(|_| rng.gen_range(b'a'..=b'z')));
+
+        let state = cbc_state(&candidate, key, iv);
+        let glue = xor(&xor(&state, iv), &original[..16]);
+        if glue.iter().any(|byte| *byte == b'\n' || *byte == b'\r') {
+            continue;
+        }
+
+        candidate.extend_from_slice(&glue);
+        candidate.extend_from_slice(&original[16..]);
+        return candidate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn s07e02() {
+        let original = b"alert('MZA who was that?');\n";
+        let hash = cbc_mac_hash(original);
+        assert_eq!(hex::encode(hash), "296b8d7cb78a243dda4d0a61d33bbdd1");
+
+        let forged = forge_snippet(
+            original,
+            b"alert('Ayo, the Wu is back!');",
+            b"YELLOW SUBMARINE",
+            &[0; 16],
+        );
+        assert!(forged.starts_with(b"alert('Ayo, the Wu is back!');//"));
+        assert_eq!(forged.iter().filter(|byte| **byte == b'\n').count(), 1);
+        assert_eq!(cbc_mac_hash(&forged), hash);
+    }
+}
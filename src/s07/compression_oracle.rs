@@ -0,0 +1,127 @@
+use crate::{
+    s02::aes_cbc::aes128_cbc_encode,
+    s03::aes_ctr::aes128_ctr_encode,
+    util::{deflate::deflate, generators::generate_aes_key},
+};
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=\n";
+
+// Literals 144-255 take 9 bits in the fixed Huffman code, so each of these
+// shifts the compressed length by one bit.
+const ALIGNMENT_BYTES: &[u8] = &[0x90, 0x91, 0x92, 0x93, 0x94, 0x95, 0x96];
+// Literals below 144 take 8 bits, these never appear in the request.
+const JUNK_BYTES: &[u8] = &[
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x0b, 0x0c, 0x0e, 0x0f, 0x10, 0x11, 0x12,
+    0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f,
+];
+const WRONG_GUESS: u8 = 0x7f;
+
+pub enum CompressionCipher {
+    Ctr,
+    Cbc,
+}
+
+pub struct CompressionOracle {
+    session_id: Vec<u8>,
+    cipher: CompressionCipher,
+}
+
+impl CompressionOracle {
+    pub fn new(session_id: &[u8], cipher: CompressionCipher) -> CompressionOracle {
+        CompressionOracle {
+            session_id: session_id.to_vec(),
+            cipher,
+        }
+    }
+
+    fn format_request(&self, payload: &[u8]) -> Vec<u8> {
+        let mut request = Vec::new();
+        request.extend_from_slice(b"POST / HTTP/1.1\nHost: hapless.com\nCookie: sessionid=");
+        request.extend_from_slice(&self.session_id);
+        request.extend_from_slice(format!("\nContent-Length: {}\n", payload.len()).as_bytes());
+        request.extend_from_slice(payload);
+        request
+    }
+
+    pub fn encrypted_length(&self, payload: &[u8]) -> usize {
+        let compressed = deflate(&self.format_request(payload));
+        let key = generate_aes_key();
+        let iv = generate_aes_key();
+        let cipher = match self.cipher {
+            CompressionCipher::Ctr => aes128_ctr_encode(&compressed, &key, &iv[..8]),
+            CompressionCipher::Cbc => aes128_cbc_encode(&compressed, &key, &iv),
+        }
+        .unwrap();
+        cipher.len()
+    }
+}
+
+fn payload(padding: &[u8], guess: &[u8], next: u8) -> Vec<u8> {
+    [padding, guess, &[next]].concat()
+}
+
+// For every bit alignment, adds junk until a wrong guess just crosses into a
+// longer ciphertext. A correct guess compresses better and stays below.
+fn paddings(oracle: &CompressionOracle, guess: &[u8]) -> Vec<Vec<u8>> {
+    let mut result = Vec::new();
+    for alignment in 0..=ALIGNMENT_BYTES.len() {
+        let mut padding = ALIGNMENT_BYTES[..alignment].to_vec();
+        let base_length = oracle.encrypted_length(&payload(&padding, guess, WRONG_GUESS));
+        for junk in JUNK_BYTES {
+            padding.push(*junk);
+            if oracle.encrypted_length(&payload(&padding, guess, WRONG_GUESS)) > base_length {
+                result.push(padding);
+                break;
+            }
+        }
+    }
+    result
+}
+
+pub fn compression_attack(oracle: &CompressionOracle, known_prefix: &[u8]) -> Vec<u8> {
+    let mut guess = known_prefix.to_vec();
+
+    loop {
+        let paddings = paddings(oracle, &guess);
+
+        let (_, next) = ALPHABET
+            .iter()
+            .map(|c| {
+                let score: usize = paddings
+                    .iter()
+                    .map(|padding| oracle.encrypted_length(&payload(padding, &guess, *c)))
+                    .sum();
+                (score, *c)
+            })
+            .min()
+            .unwrap();
+
+        if next == b'\n' {
+            break;
+        }
+        guess.push(next);
+        println!("{}", String::from_utf8_lossy(&guess[known_prefix.len()..]));
+    }
+
+    guess[known_prefix.len()..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const SESSION_ID: &[u8] = b"TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+
+    #[test]
+    fn s07e03_ctr() {
+        let oracle = CompressionOracle::new(SESSION_ID, CompressionCipher::Ctr);
+        assert_eq!(compression_attack(&oracle, b"sessionid="), SESSION_ID);
+    }
+
+    #[test]
+    fn s07e03_cbc() {
+        let oracle = CompressionOracle::new(SESSION_ID, CompressionCipher::Cbc);
+        assert_eq!(compression_attack(&oracle, b"sessionid="), SESSION_ID);
+    }
+}
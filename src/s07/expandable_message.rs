@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use super::{
+    md_hash::{random_block, MdHash, BLOCK_SIZE},
+    multicollision::find_collision_between,
+};
+
+type Block = [u8; BLOCK_SIZE];
+
+// Kelsey-Schneier: k collisions between a single block and 2^(k-1-i) + 1
+// blocks allow messages of any length between k and k + 2^k - 1 blocks which
+// all end in the same state.
+pub struct ExpandableMessage {
+    pieces: Vec<(Block, Vec<u8>)>,
+    state: u32,
+}
+
+impl ExpandableMessage {
+    pub fn new(hash: &MdHash, state: u32, k: usize) -> ExpandableMessage {
+        let mut pieces = Vec::new();
+        let mut state = state;
+        for i in 0..k {
+            let dummy = vec![0; (1 << (k - 1 - i)) * BLOCK_SIZE];
+            let dummy_state = hash.compress_blocks(state, &dummy);
+
+            let (short, last, next_state) = find_collision_between(hash, state, dummy_state);
+            pieces.push((short, [dummy, last.to_vec()].concat()));
+            state = next_state;
+        }
+        ExpandableMessage { pieces, state }
+    }
+
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    pub fn min_blocks(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn max_blocks(&self) -> usize {
+        self.pieces.len() + (1 << self.pieces.len()) - 1
+    }
+
+    pub fn message(&self, blocks: usize) -> Vec<u8> {
+        if blocks < self.min_blocks() || blocks > self.max_blocks() {
+            panic!("expandable message can not have {blocks} blocks");
+        }
+        let k = self.pieces.len();
+        let extra = blocks - k;
+        self.pieces
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (short, long))| {
+                if (extra >> (k - 1 - i)) & 1 == 1 {
+                    long.clone()
+                } else {
+                    short.to_vec()
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn second_preimage(hash: &MdHash, target: &[u8]) -> Vec<u8> {
+    let target_blocks = target.len() / BLOCK_SIZE;
+    if target_blocks < 4 {
+        panic!("target message is too short");
+    }
+    let k = target_blocks.ilog2() as usize;
+    let expandable = ExpandableMessage::new(hash, hash.initial_state(), k);
+
+    // intermediate states after j blocks which the expandable message can reach
+    let mut states: HashMap<u32, usize> = HashMap::new();
+    let mut state = hash.initial_state();
+    for (j, block) in target.chunks_exact(BLOCK_SIZE).enumerate() {
+        state = hash.compress(state, block);
+        let blocks = j + 1;
+        if blocks > expandable.min_blocks() && blocks <= expandable.max_blocks() + 1 {
+            states.insert(state, blocks);
+        }
+    }
+
+    loop {
+        let bridge = random_block();
+        if let Some(blocks) = states.get(&hash.compress(expandable.state(), &bridge)) {
+            return [
+                &expandable.message(blocks - 1),
+                &bridge[..],
+                &target[blocks * BLOCK_SIZE..],
+            ]
+            .concat();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn expandable_message() {
+        let hash = MdHash::new(16);
+        let expandable = ExpandableMessage::new(&hash, hash.initial_state(), 4);
+        assert_eq!(expandable.max_blocks(), 19);
+
+        for blocks in expandable.min_blocks()..=expandable.max_blocks() {
+            let message = expandable.message(blocks);
+            assert_eq!(message.len(), blocks * BLOCK_SIZE);
+            assert_eq!(
+                hash.compress_blocks(hash.initial_state(), &message),
+                expandable.state()
+            );
+        }
+    }
+
+    #[test]
+    fn s07e05() {
+        let hash = MdHash::new(16);
+        let target: Vec<u8> = (0..(1 << 10) * BLOCK_SIZE + 5)
+            .map(|_| rand::random())
+            .collect();
+
+        let forged = second_preimage(&hash, &target);
+        assert_ne!(forged, target);
+        assert_eq!(forged.len(), target.len());
+        assert_eq!(hash.hash(&forged), hash.hash(&target));
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::{HashMap, HashSet};
+
+use indicatif::ProgressIterator;
+
+use crate::util::progress_bar::create_progress_bar;
+
+use super::{
+    md_hash::{random_block, MdHash, BLOCK_SIZE},
+    multicollision::find_collision_between,
+};
+
+type Block = [u8; BLOCK_SIZE];
+
+// A binary tree of 2^k leaf states. blocks[l][i] leads from states[l][i] to
+// states[l + 1][i / 2], so every leaf reaches the root after k blocks.
+pub struct DiamondStructure {
+    states: Vec<Vec<u32>>,
+    blocks: Vec<Vec<Block>>,
+    leaves: HashMap<u32, usize>,
+}
+
+impl DiamondStructure {
+    pub fn new(hash: &MdHash, k: usize) -> DiamondStructure {
+        let mut leaf_states = HashSet::new();
+        while leaf_states.len() < 1 << k {
+            leaf_states.insert(rand::random::<u32>() >> (32 - hash.state_bits()));
+        }
+        let mut states = vec![leaf_states.into_iter().collect::<Vec<u32>>()];
+        let mut blocks = Vec::new();
+
+        println!(" building diamond structure...");
+        let progress_bar = create_progress_bar((1 << k) - 1);
+        for level in 0..k {
+            let mut level_blocks = Vec::new();
+            let mut next_states = Vec::new();
+            for pair in states[level].chunks_exact(2) {
+                let (a, b, state) = find_collision_between(hash, pair[0], pair[1]);
+                level_blocks.push(a);
+                level_blocks.push(b);
+                next_states.push(state);
+                progress_bar.inc(1);
+            }
+            blocks.push(level_blocks);
+            states.push(next_states);
+        }
+        progress_bar.finish();
+
+        let leaves = states[0]
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (*state, i))
+            .collect();
+
+        DiamondStructure {
+            states,
+            blocks,
+            leaves,
+        }
+    }
+
+    pub fn k(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.states[0].len()
+    }
+
+    pub fn root(&self) -> u32 {
+        self.states[self.k()][0]
+    }
+
+    pub fn leaf_index(&self, state: u32) -> Option<usize> {
+        self.leaves.get(&state).copied()
+    }
+
+    // The k blocks leading from the given leaf to the root.
+    pub fn path(&self, leaf: usize) -> Vec<u8> {
+        (0..self.k())
+            .flat_map(|level| self.blocks[level][leaf >> level])
+            .collect()
+    }
+
+    // Hash of any message with a prefix of prefix_len bytes herded into the root.
+    pub fn predict(&self, hash: &MdHash, prefix_len: usize) -> u32 {
+        hash.finalize(
+            self.root(),
+            prefix_len.next_multiple_of(BLOCK_SIZE) + (1 + self.k()) * BLOCK_SIZE,
+        )
+    }
+
+    // The glue fills up a partial last block of the prefix before the full
+    // block that is searched for a leaf.
+    pub fn herd(&self, hash: &MdHash, prefix: &[u8]) -> Vec<u8> {
+        let (full_blocks, partial_block) =
+            prefix.split_at(prefix.len() - prefix.len() % BLOCK_SIZE);
+        let glue_len = prefix.len().next_multiple_of(BLOCK_SIZE) - prefix.len() + BLOCK_SIZE;
+        let state = hash.compress_blocks(hash.initial_state(), full_blocks);
+
+        (0..)
+            .progress_with(create_progress_bar(
+                1 << (hash.state_bits() as usize - self.k()),
+            ))
+            .find_map(|_| {
+                let glue = &[random_block(), random_block()].concat()[..glue_len];
+                self.leaf_index(hash.compress_blocks(state, &[partial_block, glue].concat()))
+                    .map(|leaf| [prefix, glue, &self.path(leaf)].concat())
+            })
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn diamond_structure() {
+        let hash = MdHash::new(16);
+        let diamond = DiamondStructure::new(&hash, 4);
+        assert_eq!(diamond.leaf_count(), 16);
+
+        for leaf in 0..diamond.leaf_count() {
+            let state = diamond.states[0][leaf];
+            assert_eq!(diamond.leaf_index(state), Some(leaf));
+            assert_eq!(
+                hash.compress_blocks(state, &diamond.path(leaf)),
+                diamond.root()
+            );
+        }
+    }
+
+    #[test]
+    fn herd_block_prefix() {
+        let hash = MdHash::new(16);
+        let diamond = DiamondStructure::new(&hash, 4);
+
+        let prefix = [random_block(), random_block()].concat();
+        let message = diamond.herd(&hash, &prefix);
+        assert_eq!(message.len(), (3 + diamond.k()) * BLOCK_SIZE);
+        assert_eq!(hash.hash(&message), diamond.predict(&hash, prefix.len()));
+    }
+
+    #[test]
+    fn s07e06() {
+        let hash = MdHash::new(20);
+        let diamond = DiamondStructure::new(&hash, 8);
+
+        let prefix = b"2023 season: Bears 3, Packers 1, Vikings 2, Lions 0 ";
+        let prediction = diamond.predict(&hash, prefix.len());
+
+        let message = diamond.herd(&hash, prefix);
+        assert!(message.starts_with(prefix));
+        assert_eq!(hash.hash(&message), prediction);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::s04::md4::{
+    md4, md4_message_bytes, md4_states, md4_step, md4_step_inverse, md4_step_shift, md4_step_word,
+    MD4_INITIAL_STATE,
+};
+
+#[derive(Clone, Copy)]
+enum Constraint {
+    Zero,
+    One,
+    Equal(usize),
+}
+
+use Constraint::{Equal, One, Zero};
+
+// Index of a_i, d_i, c_i and b_i in the output of md4_states.
+const fn a(i: usize) -> usize {
+    4 * i
+}
+const fn d(i: usize) -> usize {
+    4 * i + 1
+}
+const fn c(i: usize) -> usize {
+    4 * i + 2
+}
+const fn b(i: usize) -> usize {
+    4 * i + 3
+}
+
+// Sufficient conditions from Wang et al., bits are counted from 1 as in the paper.
+const ROUND_1_CONDITIONS: &[(usize, &[(u32, Constraint)])] = &[
+    (a(1), &[(7, Equal(b(0)))]),
+    (d(1), &[(7, Zero), (8, Equal(a(1))), (11, Equal(a(1)))]),
+    (c(1), &[(7, One), (8, One), (11, Zero), (26, Equal(d(1)))]),
+    (b(1), &[(7, One), (8, Zero), (11, Zero), (26, Zero)]),
+    (a(2), &[(8, One), (11, One), (26, Zero), (14, Equal(b(1)))]),
+    (
+        d(2),
+        &[
+            (14, Zero),
+            (19, Equal(a(2))),
+            (20, Equal(a(2))),
+            (21, Equal(a(2))),
+            (22, Equal(a(2))),
+            (26, One),
+        ],
+    ),
+    (
+        c(2),
+        &[
+            (13, Equal(d(2))),
+            (14, Zero),
+            (15, Equal(d(2))),
+            (19, Zero),
+            (20, Zero),
+            (21, One),
+            (22, Zero),
+        ],
+    ),
+    (
+        b(2),
+        &[
+            (13, One),
+            (14, One),
+            (15, Zero),
+            (17, Equal(c(2))),
+            (19, Zero),
+            (20, Zero),
+            (21, Zero),
+            (22, Zero),
+        ],
+    ),
+    (
+        a(3),
+        &[
+            (13, One),
+            (14, One),
+            (15, One),
+            (17, Zero),
+            (19, Zero),
+            (20, Zero),
+            (21, Zero),
+            (22, One),
+            (23, Equal(b(2))),
+            (26, Equal(b(2))),
+        ],
+    ),
+    (
+        d(3),
+        &[
+            (13, One),
+            (14, One),
+            (15, One),
+            (17, Zero),
+            (20, Zero),
+            (21, One),
+            (22, One),
+            (23, Zero),
+            (26, One),
+            (30, Equal(a(3))),
+        ],
+    ),
+    (
+        c(3),
+        &[
+            (17, One),
+            (20, Zero),
+            (21, Zero),
+            (22, Zero),
+            (23, Zero),
+            (26, Zero),
+            (30, One),
+            (32, Equal(d(3))),
+        ],
+    ),
+    (
+        b(3),
+        &[
+            (20, Zero),
+            (21, One),
+            (22, One),
+            (23, Equal(c(3))),
+            (26, One),
+            (30, Zero),
+            (32, Zero),
+        ],
+    ),
+    (
+        a(4),
+        &[
+            (23, Zero),
+            (26, Zero),
+            (27, Equal(b(3))),
+            (29, Equal(b(3))),
+            (30, One),
+            (32, Zero),
+        ],
+    ),
+    (
+        d(4),
+        &[
+            (23, Zero),
+            (26, Zero),
+            (27, One),
+            (29, One),
+            (30, Zero),
+            (32, One),
+        ],
+    ),
+    (
+        c(4),
+        &[
+            (19, Equal(d(4))),
+            (23, One),
+            (26, One),
+            (27, Zero),
+            (29, Zero),
+            (30, Zero),
+        ],
+    ),
+    (
+        b(4),
+        &[(19, Zero), (26, One), (27, One), (29, One), (30, Zero)],
+    ),
+];
+
+const A5_CONDITIONS: &[(u32, Constraint)] = &[
+    (19, Equal(c(4))),
+    (26, One),
+    (27, Zero),
+    (29, One),
+    (32, One),
+];
+
+const D5_CONDITIONS: &[(u32, Constraint)] = &[
+    (19, Equal(a(5))),
+    (26, Equal(b(4))),
+    (27, Equal(b(4))),
+    (29, Equal(b(4))),
+    (32, Equal(b(4))),
+];
+
+fn required_bit(states: &[u32], bit: u32, constraint: Constraint) -> u32 {
+    match constraint {
+        Zero => 0,
+        One => 1,
+        Equal(other) => (states[other] >> (bit - 1)) & 1,
+    }
+}
+
+fn satisfies(states: &[u32], index: usize, conditions: &[(u32, Constraint)]) -> bool {
+    conditions.iter().all(|(bit, constraint)| {
+        (states[index] >> (bit - 1)) & 1 == required_bit(states, *bit, *constraint)
+    })
+}
+
+fn enforce(states: &[u32], index: usize, conditions: &[(u32, Constraint)]) -> u32 {
+    conditions
+        .iter()
+        .fold(states[index], |value, (bit, constraint)| {
+            let mask = 1 << (bit - 1);
+            (value & !mask) | (required_bit(states, *bit, *constraint) << (bit - 1))
+        })
+}
+
+// Single-step modification: every round 1 state is corrected directly and the
+// message word is recomputed from it.
+fn single_step_modification(x: &mut [u32; 16]) -> Vec<u32> {
+    let [a0, b0, c0, d0] = MD4_INITIAL_STATE;
+    let mut states = vec![a0, d0, c0, b0];
+    for (step, (index, conditions)) in ROUND_1_CONDITIONS.iter().enumerate() {
+        let value = md4_step(&states, x, step);
+        states.push(value);
+        states[*index] = enforce(&states, *index, conditions);
+        x[step] = md4_step_inverse(&states, step);
+    }
+    states
+}
+
+// Multi-step modification for a round 2 step: a wrong bit is flipped by
+// adding or subtracting a power of two to the message word, and the next four
+// round 1 message words are recomputed so that the round 1 states they produce
+// stay the same.
+fn multi_step_modification(
+    x: &mut [u32; 16],
+    states: &mut Vec<u32>,
+    step: usize,
+    conditions: &[(u32, Constraint)],
+) {
+    let word = md4_step_word(step);
+    let shift = md4_step_shift(step);
+    states.truncate(step + 4);
+    let value = md4_step(states, x, step);
+    states.push(value);
+
+    for (bit, constraint) in conditions {
+        if (states[step + 4] >> (bit - 1)) & 1 == required_bit(states, *bit, *constraint) {
+            continue;
+        }
+        let sum_bit = (bit - 1 + 32 - shift) % 32;
+        let sum = states[step + 4].rotate_right(shift);
+        x[word] = if (sum >> sum_bit) & 1 == 0 {
+            x[word].wrapping_add(1 << sum_bit)
+        } else {
+            x[word].wrapping_sub(1 << sum_bit)
+        };
+
+        states[word + 4] = md4_step(states, x, word);
+        (word + 1..word + 5)
+            .for_each(|following| x[following] = md4_step_inverse(states, following));
+        states[step + 4] = md4_step(states, x, step);
+    }
+}
+
+pub fn satisfies_round_1_conditions(x: &[u32; 16]) -> bool {
+    let states = md4_states(MD4_INITIAL_STATE, x);
+    ROUND_1_CONDITIONS
+        .iter()
+        .all(|(index, conditions)| satisfies(&states, *index, conditions))
+}
+
+pub fn collision_candidate() -> [u32; 16] {
+    let mut x: [u32; 16] = rand::random();
+    let mut states = single_step_modification(&mut x);
+    multi_step_modification(&mut x, &mut states, 16, A5_CONDITIONS);
+    multi_step_modification(&mut x, &mut states, 17, D5_CONDITIONS);
+    x
+}
+
+// M' = M + (0, 2^31, 2^31 - 2^28, 0, ..., 0, -2^16, 0, 0, 0)
+pub fn apply_differential(x: &[u32; 16]) -> [u32; 16] {
+    let mut other = *x;
+    other[1] = other[1].wrapping_add(1 << 31);
+    other[2] = other[2].wrapping_add(1 << 31).wrapping_sub(1 << 28);
+    other[12] = other[12].wrapping_sub(1 << 16);
+    other
+}
+
+pub fn md4_collision() -> (Vec<u8>, Vec<u8>) {
+    let mut tries: u64 = 0;
+    loop {
+        tries += 1;
+        let x = collision_candidate();
+        let message = md4_message_bytes(&x);
+        let other = md4_message_bytes(&apply_differential(&x));
+        if md4(&message) == md4(&other) {
+            println!("Found collision after {tries} tries");
+            return (message, other);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn md4_message_modification() {
+        for _ in 0..100 {
+            let mut x = rand::random();
+            single_step_modification(&mut x);
+            assert!(satisfies_round_1_conditions(&x));
+        }
+    }
+
+    #[test]
+    fn s07e07() {
+        let (message, other) = md4_collision();
+        assert_ne!(message, other);
+        assert_eq!(md4(&message), md4(&other));
+    }
+}
@@ -0,0 +1,104 @@
+use aes::{
+    cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+    Aes128,
+};
+
+use crate::util::bits::u64_to_big_endian;
+
+pub const BLOCK_SIZE: usize = 16;
+
+const INITIAL_STATE: u32 = 0x67452301;
+
+// Merkle-Damgard hash with a truncated AES compression function
+// C(h, m) = AES_h(m), where only the top state_bits of the state are kept.
+#[derive(Clone, Copy)]
+pub struct MdHash {
+    state_bits: u32,
+}
+
+impl MdHash {
+    pub fn new(state_bits: u32) -> MdHash {
+        if !(16..=32).contains(&state_bits) {
+            panic!("state must have between 16 and 32 bits");
+        }
+        MdHash { state_bits }
+    }
+
+    pub fn state_bits(&self) -> u32 {
+        self.state_bits
+    }
+
+    pub fn initial_state(&self) -> u32 {
+        INITIAL_STATE >> (32 - self.state_bits)
+    }
+
+    pub fn compress(&self, state: u32, block: &[u8]) -> u32 {
+        let mut key = [0; 16];
+        key[..4].copy_from_slice(&state.to_be_bytes());
+        let aes = Aes128::new(GenericArray::from_slice(&key));
+
+        let mut output = GenericArray::clone_from_slice(block);
+        aes.encrypt_block(&mut output);
+
+        u32::from_be_bytes(output[..4].try_into().unwrap()) >> (32 - self.state_bits)
+    }
+
+    pub fn compress_blocks(&self, state: u32, blocks: &[u8]) -> u32 {
+        if !blocks.len().is_multiple_of(BLOCK_SIZE) {
+            panic!("Message len is not a multiple of the block size.");
+        }
+        blocks
+            .chunks_exact(BLOCK_SIZE)
+            .fold(state, |state, block| self.compress(state, block))
+    }
+
+    // Processes the padding of a message of message_len bytes, which has
+    // already been compressed into state.
+    pub fn finalize(&self, state: u32, message_len: usize) -> u32 {
+        if !message_len.is_multiple_of(BLOCK_SIZE) {
+            panic!("Message len is not a multiple of the block size.");
+        }
+        self.compress_blocks(state, &get_padding(message_len, message_len))
+    }
+
+    pub fn hash(&self, message: &[u8]) -> u32 {
+        let mut expanded_message = message.to_vec();
+        expanded_message.append(&mut get_padding(message.len(), message.len()));
+        self.compress_blocks(self.initial_state(), &expanded_message)
+    }
+}
+
+pub fn random_block() -> [u8; BLOCK_SIZE] {
+    rand::random()
+}
+
+fn get_padding(true_message_length: usize, padding_message_length: usize) -> Vec<u8> {
+    let mut padding = Vec::<u8>::new();
+    padding.push(0x80);
+
+    while (padding.len() + true_message_length) % BLOCK_SIZE != 8 {
+        padding.push(0x00);
+    }
+
+    padding.append(&mut u64_to_big_endian((padding_message_length * 8) as u64).into());
+
+    padding
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn md_hash_test() {
+        let hash = MdHash::new(16);
+        assert_eq!(hash.initial_state(), 0x6745);
+        assert!(hash.hash(b"YELLOW SUBMARINE") < 1 << 16);
+        assert_ne!(hash.hash(b""), hash.hash(&[0; 16]));
+
+        let message = [random_block(), random_block()].concat();
+        let state = hash.compress_blocks(hash.initial_state(), &message);
+        assert_eq!(hash.finalize(state, message.len()), hash.hash(&message));
+    }
+}
@@ -0,0 +1,10 @@
+pub mod cbc_mac;
+pub mod cbc_mac_hash;
+pub mod compression_oracle;
+pub mod expandable_message;
+pub mod herding;
+pub mod md4_collision;
+pub mod md_hash;
+pub mod multicollision;
+pub mod rc4;
+pub mod rc4_bias;
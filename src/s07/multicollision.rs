@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use super::md_hash::{random_block, MdHash, BLOCK_SIZE};
+
+type Block = [u8; BLOCK_SIZE];
+
+// Returns two different blocks which compress to the same state.
+pub fn find_collision(hash: &MdHash, state: u32) -> (Block, Block, u32) {
+    let mut seen: HashMap<u32, Block> = HashMap::new();
+    loop {
+        let block = random_block();
+        let next_state = hash.compress(state, &block);
+        match seen.get(&next_state) {
+            Some(other) if *other != block => return (*other, block, next_state),
+            _ => {
+                seen.insert(next_state, block);
+            }
+        }
+    }
+}
+
+// Returns blocks a and b with C(state_a, a) = C(state_b, b).
+pub fn find_collision_between(hash: &MdHash, state_a: u32, state_b: u32) -> (Block, Block, u32) {
+    let mut seen_a: HashMap<u32, Block> = HashMap::new();
+    let mut seen_b: HashMap<u32, Block> = HashMap::new();
+    loop {
+        let block_a = random_block();
+        let next_a = hash.compress(state_a, &block_a);
+        if let Some(block_b) = seen_b.get(&next_a) {
+            return (block_a, *block_b, next_a);
+        }
+        seen_a.insert(next_a, block_a);
+
+        let block_b = random_block();
+        let next_b = hash.compress(state_b, &block_b);
+        if let Some(block_a) = seen_a.get(&next_b) {
+            return (*block_a, block_b, next_b);
+        }
+        seen_b.insert(next_b, block_b);
+    }
+}
+
+// Joux's construction: n successive single block collisions yield 2^n
+// messages of n blocks with the same final state.
+pub struct Multicollision {
+    blocks: Vec<(Block, Block)>,
+    state: u32,
+}
+
+impl Multicollision {
+    pub fn new(hash: &MdHash, state: u32, n: usize) -> Multicollision {
+        let mut multicollision = Multicollision {
+            blocks: Vec::new(),
+            state,
+        };
+        for _ in 0..n {
+            multicollision.extend(hash);
+        }
+        multicollision
+    }
+
+    pub fn extend(&mut self, hash: &MdHash) {
+        let (a, b, state) = find_collision(hash, self.state);
+        self.blocks.push((a, b));
+        self.state = state;
+    }
+
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn message_count(&self) -> usize {
+        1 << self.blocks.len()
+    }
+
+    // Bit i of index selects the block of the i-th collision.
+    pub fn message(&self, index: usize) -> Vec<u8> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (a, b))| if (index >> i) & 1 == 0 { *a } else { *b })
+            .collect()
+    }
+
+    pub fn messages(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        (0..self.message_count()).map(|index| self.message(index))
+    }
+}
+
+// Finds two messages colliding in f(M) || g(M), where f is the weaker hash.
+pub fn cascade_collision(f: &MdHash, g: &MdHash) -> (Vec<u8>, Vec<u8>) {
+    let mut multicollision =
+        Multicollision::new(f, f.initial_state(), (g.state_bits() / 2) as usize);
+
+    loop {
+        println!(
+            "Searching {} colliding messages for a collision in g",
+            multicollision.message_count()
+        );
+        let mut seen: HashMap<u32, Vec<u8>> = HashMap::new();
+        for message in multicollision.messages() {
+            let g_hash = g.hash(&message);
+            if let Some(other) = seen.get(&g_hash) {
+                return (other.clone(), message);
+            }
+            seen.insert(g_hash, message);
+        }
+        multicollision.extend(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn joux_multicollision() {
+        let hash = MdHash::new(16);
+        let multicollision = Multicollision::new(&hash, hash.initial_state(), 4);
+        let messages: Vec<Vec<u8>> = multicollision.messages().collect();
+
+        assert_eq!(messages.len(), 16);
+        let expected = hash.hash(&messages[0]);
+        for (i, message) in messages.iter().enumerate() {
+            assert_eq!(message.len(), 4 * BLOCK_SIZE);
+            assert_eq!(hash.hash(message), expected);
+            assert!(!messages[..i].contains(message));
+        }
+    }
+
+    #[test]
+    fn s07e04() {
+        let f = MdHash::new(16);
+        let g = MdHash::new(24);
+
+        let (a, b) = cascade_collision(&f, &g);
+        assert_ne!(a, b);
+        assert_eq!(f.hash(&a), f.hash(&b));
+        assert_eq!(g.hash(&a), g.hash(&b));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::util::error::{CryptoError, Result};
+
+pub struct Rc4 {
+    s: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    pub fn new(key: &[u8]) -> Result<Rc4> {
+        if key.is_empty() || key.len() > 256 {
+            return Err(CryptoError::InvalidKeyLength(key.len()));
+        }
+        let mut s = [0; 256];
+        for (i, value) in s.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+
+        let mut j: u8 = 0;
+        for i in 0..256 {
+            j = j.wrapping_add(s[i]).wrapping_add(key[i % key.len()]);
+            s.swap(i, j as usize);
+        }
+
+        Ok(Rc4 { s, i: 0, j: 0 })
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        self.i = self.i.wrapping_add(1);
+        self.j = self.j.wrapping_add(self.s[self.i as usize]);
+        self.s.swap(self.i as usize, self.j as usize);
+        self.s[self.s[self.i as usize].wrapping_add(self.s[self.j as usize]) as usize]
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte ^= self.next_byte();
+        }
+    }
+}
+
+pub fn rc4_encode(plain: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    let mut cipher = plain.to_vec();
+    Rc4::new(key)?.apply_keystream(&mut cipher);
+    Ok(cipher)
+}
+
+pub fn rc4_decode(cipher: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    rc4_encode(cipher, key)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn rc4_kat() {
+        assert_eq!(
+            hex::encode(rc4_encode(b"Plaintext", b"Key").unwrap()),
+            "bbf316e8d940af0ad3"
+        );
+        assert_eq!(
+            hex::encode(rc4_encode(b"pedia", b"Wiki").unwrap()),
+            "1021bf0420"
+        );
+        assert_eq!(
+            hex::encode(rc4_encode(b"Attack at dawn", b"Secret").unwrap()),
+            "45a01f645fc35b383552544b9bf5"
+        );
+
+        // RFC 6229, 40-bit key, offset 0
+        let mut keystream = [0; 16];
+        Rc4::new(&[1, 2, 3, 4, 5])
+            .unwrap()
+            .apply_keystream(&mut keystream);
+        assert_eq!(hex::encode(keystream), "b2396305f03dc027ccc3524a0a1118a8");
+
+        assert_eq!(
+            rc4_decode(&rc4_encode(b"roundtrip", b"k").unwrap(), b"k").unwrap(),
+            b"roundtrip"
+        );
+    }
+
+    #[test]
+    fn rc4_errors() {
+        assert_eq!(
+            rc4_encode(b"plain", b"").err(),
+            Some(CryptoError::InvalidKeyLength(0))
+        );
+        assert_eq!(
+            rc4_encode(b"plain", &[0; 257]).err(),
+            Some(CryptoError::InvalidKeyLength(257))
+        );
+    }
+}
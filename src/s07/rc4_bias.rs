@@ -0,0 +1,155 @@
+use std::thread;
+
+use indicatif::ProgressBar;
+
+use crate::util::progress_bar::create_progress_bar;
+
+use super::rc4::rc4_encode;
+
+// The keystream bytes Z16 and Z32 are biased towards 240 and 224.
+const Z16_BIAS: u8 = 240;
+const Z32_BIAS: u8 = 224;
+
+const CHUNK_SIZE: usize = 1 << 16;
+
+pub struct Rc4CookieOracle {
+    cookie: Vec<u8>,
+}
+
+impl Rc4CookieOracle {
+    pub fn new(cookie: &[u8]) -> Rc4CookieOracle {
+        Rc4CookieOracle {
+            cookie: cookie.to_vec(),
+        }
+    }
+
+    pub fn encrypt(&self, request: &[u8]) -> Vec<u8> {
+        let key: [u8; 16] = rand::random();
+        rc4_encode(&[request, &self.cookie].concat(), &key).unwrap()
+    }
+}
+
+type Counts = [[u64; 256]; 2];
+
+fn count_cipher(cipher: &[u8], counts: &mut Counts) {
+    counts[0][cipher[15] as usize] += 1;
+    if cipher.len() > 31 {
+        counts[1][cipher[31] as usize] += 1;
+    }
+}
+
+fn count_chunk(oracle: &Rc4CookieOracle, request: &[u8], samples: usize, counts: &mut Counts) {
+    for _ in 0..samples {
+        count_cipher(&oracle.encrypt(request), counts);
+    }
+}
+
+// Counts the ciphertext bytes at positions 15 and 31, split across all cores.
+fn count_bytes(
+    oracle: &Rc4CookieOracle,
+    request: &[u8],
+    samples: usize,
+    progress_bar: &ProgressBar,
+) -> Counts {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunks = samples.div_ceil(CHUNK_SIZE);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread_index| {
+                scope.spawn(move || {
+                    let mut counts = [[0; 256]; 2];
+                    for _ in (thread_index..chunks).step_by(threads) {
+                        count_chunk(oracle, request, CHUNK_SIZE, &mut counts);
+                        progress_bar.inc(1);
+                    }
+                    counts
+                })
+            })
+            .collect();
+
+        let mut total = [[0; 256]; 2];
+        for handle in handles {
+            let counts = handle.join().unwrap();
+            for (total_row, row) in total.iter_mut().zip(counts) {
+                for (sum, count) in total_row.iter_mut().zip(row) {
+                    *sum += count;
+                }
+            }
+        }
+        total
+    })
+}
+
+fn most_frequent(counts: &[u64; 256]) -> u8 {
+    (0..=255).max_by_key(|byte| counts[*byte as usize]).unwrap()
+}
+
+pub fn rc4_bias_attack(oracle: &Rc4CookieOracle, samples: usize) -> Vec<u8> {
+    let cookie_len = oracle.encrypt(&[]).len();
+    if cookie_len > 32 {
+        panic!("only the first 32 Bytes of the cookie can be recovered");
+    }
+    let mut cookie = vec![0; cookie_len];
+
+    // a prefix of length p moves cookie bytes 15 - p and 31 - p onto the biased positions
+    let prefix_lengths: Vec<usize> = (0..16).filter(|p| 15 - p < cookie_len).collect();
+    let progress_bar =
+        create_progress_bar((prefix_lengths.len() * samples.div_ceil(CHUNK_SIZE)) as u64);
+
+    for prefix_len in prefix_lengths {
+        let request = vec![b'A'; prefix_len];
+        let counts = count_bytes(oracle, &request, samples, &progress_bar);
+
+        cookie[15 - prefix_len] = most_frequent(&counts[0]) ^ Z16_BIAS;
+        if 31 - prefix_len < cookie_len {
+            cookie[31 - prefix_len] = most_frequent(&counts[1]) ^ Z32_BIAS;
+        }
+    }
+    progress_bar.finish();
+
+    cookie
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::util::{base_64::Base64, xor::xor};
+
+    use super::*;
+
+    #[test]
+    fn bias_counting() {
+        let cookie = b"BE SURE TO DRINK YOUR OVALTINE!!";
+
+        // a fixed keystream sample where every fourth keystream hits the biased values
+        let mut counts = [[0; 256]; 2];
+        for i in 0..1024 {
+            let mut keystream = [i as u8; 32];
+            if i % 4 == 0 {
+                keystream[15] = Z16_BIAS;
+                keystream[31] = Z32_BIAS;
+            }
+            count_cipher(&xor(cookie, &keystream), &mut counts);
+        }
+        assert_eq!(counts[0].iter().sum::<u64>(), 1024);
+        assert_eq!(most_frequent(&counts[0]) ^ Z16_BIAS, cookie[15]);
+        assert_eq!(most_frequent(&counts[1]) ^ Z32_BIAS, cookie[31]);
+
+        // short ciphertexts only count towards Z16
+        let oracle = Rc4CookieOracle::new(b"cookie");
+        let counts = count_bytes(&oracle, &[b'A'; 10], 1, &ProgressBar::hidden());
+        assert_eq!(counts[0].iter().sum::<u64>(), CHUNK_SIZE as u64);
+        assert_eq!(counts[1].iter().sum::<u64>(), 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn s07e08() {
+        let cookie = Base64::new_from_string("QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F").unwrap();
+        let oracle = Rc4CookieOracle::new(cookie.to_bytes());
+
+        let recovered = rc4_bias_attack(&oracle, 1 << 24);
+        assert_eq!(recovered, b"BE SURE TO DRINK YOUR OVALTINE");
+    }
+}
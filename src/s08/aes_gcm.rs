@@ -0,0 +1,329 @@
+use crate::{
+    s03::aes_ctr::{ctr_xor_with_increment, increment_iv_big_endian},
+    util::{
+        bits::u64_to_big_endian,
+        block_cipher::{aes128, BlockCipher},
+        error::{CryptoError, Result},
+        gf128::Gf128,
+        xor::xor,
+    },
+};
+
+pub fn aes128_gcm_encrypt(
+    plain: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+) -> Result<(Vec<u8>, [u8; 16])> {
+    gcm_encrypt(&aes128(key)?, plain, iv, aad)
+}
+
+pub fn aes128_gcm_decrypt(
+    cipher: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag: &[u8; 16],
+) -> Result<Vec<u8>> {
+    gcm_decrypt(&aes128(key)?, cipher, iv, aad, tag)
+}
+
+pub fn aes128_gcm_encrypt_truncated(
+    plain: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag_len: usize,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    gcm_encrypt_truncated(&aes128(key)?, plain, iv, aad, tag_len)
+}
+
+pub fn aes128_gcm_decrypt_truncated(
+    cipher: &[u8],
+    key: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>> {
+    gcm_decrypt_truncated(&aes128(key)?, cipher, iv, aad, tag)
+}
+
+pub fn gcm_encrypt(
+    block_cipher: &impl BlockCipher,
+    plain: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+) -> Result<(Vec<u8>, [u8; 16])> {
+    let (cipher, tag) = gcm_encrypt_truncated(block_cipher, plain, iv, aad, 16)?;
+    Ok((cipher, tag.try_into().unwrap()))
+}
+
+pub fn gcm_decrypt(
+    block_cipher: &impl BlockCipher,
+    cipher: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag: &[u8; 16],
+) -> Result<Vec<u8>> {
+    gcm_decrypt_truncated(block_cipher, cipher, iv, aad, tag)
+}
+
+pub fn gcm_encrypt_truncated(
+    block_cipher: &impl BlockCipher,
+    plain: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag_len: usize,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    if !(1..=16).contains(&tag_len) {
+        return Err(CryptoError::InvalidTagLength(tag_len));
+    }
+    let h = gcm_hash_key(block_cipher)?;
+    let j0 = initial_counter(h, iv);
+
+    let cipher = gcm_ctr(block_cipher, plain, &j0)?;
+    let tag = compute_tag(block_cipher, h, &j0, aad, &cipher);
+
+    Ok((cipher, tag[..tag_len].to_vec()))
+}
+
+pub fn gcm_decrypt_truncated(
+    block_cipher: &impl BlockCipher,
+    cipher: &[u8],
+    iv: &[u8],
+    aad: &[u8],
+    tag: &[u8],
+) -> Result<Vec<u8>> {
+    if !(1..=16).contains(&tag.len()) {
+        return Err(CryptoError::InvalidTagLength(tag.len()));
+    }
+    let h = gcm_hash_key(block_cipher)?;
+    let j0 = initial_counter(h, iv);
+
+    if compute_tag(block_cipher, h, &j0, aad, cipher)[..tag.len()] != *tag {
+        return Err(CryptoError::InvalidTag);
+    }
+
+    gcm_ctr(block_cipher, cipher, &j0)
+}
+
+pub fn gcm_hash_key(block_cipher: &impl BlockCipher) -> Result<Gf128> {
+    if block_cipher.block_size() != 16 {
+        return Err(CryptoError::InvalidBlockSize(block_cipher.block_size()));
+    }
+    let mut block = vec![0; 16];
+    block_cipher.encrypt_block(&mut block);
+    Ok(Gf128::from_block(&block))
+}
+
+pub fn ghash(h: Gf128, aad: &[u8], cipher: &[u8]) -> Gf128 {
+    ghash_coefficients(aad, cipher)
+        .into_iter()
+        .fold(Gf128::zero(), |acc, coefficient| (acc + coefficient) * h)
+}
+
+// The blocks that GHASH multiplies with h^n, h^(n-1), ..., h^1, in this order.
+pub fn ghash_coefficients(aad: &[u8], cipher: &[u8]) -> Vec<Gf128> {
+    let mut coefficients: Vec<Gf128> = aad
+        .chunks(16)
+        .chain(cipher.chunks(16))
+        .map(Gf128::from_block)
+        .collect();
+
+    let length_block = [
+        u64_to_big_endian((aad.len() * 8) as u64),
+        u64_to_big_endian((cipher.len() * 8) as u64),
+    ]
+    .concat();
+    coefficients.push(Gf128::from_block(&length_block));
+
+    coefficients
+}
+
+fn initial_counter(h: Gf128, iv: &[u8]) -> Vec<u8> {
+    if iv.len() == 12 {
+        [iv, &[0, 0, 0, 1]].concat()
+    } else {
+        ghash(h, &[], iv).to_block().to_vec()
+    }
+}
+
+fn gcm_ctr(block_cipher: &impl BlockCipher, input: &[u8], j0: &[u8]) -> Result<Vec<u8>> {
+    let mut counter = j0.to_vec();
+    increment_iv_big_endian(&mut counter, 4);
+    ctr_xor_with_increment(block_cipher, input, &counter, |iv| {
+        increment_iv_big_endian(iv, 4)
+    })
+}
+
+fn compute_tag(
+    block_cipher: &impl BlockCipher,
+    h: Gf128,
+    j0: &[u8],
+    aad: &[u8],
+    cipher: &[u8],
+) -> [u8; 16] {
+    let mut mask = j0.to_vec();
+    block_cipher.encrypt_block(&mut mask);
+    xor(&ghash(h, aad, cipher).to_block(), &mask)
+        .try_into()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use aes::{cipher::KeyInit, Aes256};
+
+    use crate::util::xtea::Xtea;
+
+    use super::*;
+
+    fn check_vector(key: &str, iv: &str, plain: &str, aad: &str, cipher: &str, tag: &str) {
+        let key = hex::decode(key).unwrap();
+        let iv = hex::decode(iv).unwrap();
+        let plain = hex::decode(plain).unwrap();
+        let aad = hex::decode(aad).unwrap();
+
+        let (c, t) = aes128_gcm_encrypt(&plain, &key, &iv, &aad).unwrap();
+        assert_eq!(hex::encode(&c), cipher);
+        assert_eq!(hex::encode(t), tag);
+
+        let decrypted = aes128_gcm_decrypt(&c, &key, &iv, &aad, &t).unwrap();
+        assert_eq!(decrypted, plain);
+    }
+
+    #[test]
+    fn gcm_nist_kat() {
+        let key = "feffe9928665731c6d6a8f9467308308";
+        let plain = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39";
+        let aad = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+        check_vector(
+            "00000000000000000000000000000000",
+            "000000000000000000000000",
+            "",
+            "",
+            "",
+            "58e2fccefa7e3061367f1d57a4e7455a",
+        );
+        check_vector(
+            "00000000000000000000000000000000",
+            "000000000000000000000000",
+            "00000000000000000000000000000000",
+            "",
+            "0388dace60b6a392f328c2b971b2fe78",
+            "ab6e47d42cec13bdf53a67b21257bddf",
+        );
+        check_vector(
+            key,
+            "cafebabefacedbaddecaf888",
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255",
+            "",
+            "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985",
+            "4d5c2af327cd64a62cf35abd2ba6fab4",
+        );
+        check_vector(
+            key,
+            "cafebabefacedbaddecaf888",
+            plain,
+            aad,
+            "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+            "5bc94fbc3221a5db94fae95ae7121a47",
+        );
+        check_vector(
+            key,
+            "cafebabefacedbad",
+            plain,
+            aad,
+            "61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c742373806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f4598",
+            "3612d2e79e3b0785561be14aaca2fccb",
+        );
+        check_vector(
+            key,
+            "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b",
+            plain,
+            aad,
+            "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca701e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5",
+            "619cc5aefffe0bfa462af43c1699d050",
+        );
+    }
+
+    #[test]
+    fn gcm_aes256_kat() {
+        let key = hex::decode("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308")
+            .unwrap();
+        let aes = Aes256::new_from_slice(&key).unwrap();
+        let iv = hex::decode("cafebabefacedbaddecaf888").unwrap();
+        let plain = hex::decode("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39").unwrap();
+        let aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+
+        let (cipher, tag) = gcm_encrypt(&aes, &plain, &iv, &aad).unwrap();
+        assert_eq!(hex::encode(&cipher), "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662");
+        assert_eq!(hex::encode(tag), "76fc6ece0f4e1768cddf8853bb2d551b");
+
+        assert_eq!(gcm_decrypt(&aes, &cipher, &iv, &aad, &tag).unwrap(), plain);
+    }
+
+    #[test]
+    fn gcm_rejects_tampering() {
+        let key = [0x42; 16];
+        let iv = [0x24; 12];
+        let (mut cipher, tag) =
+            aes128_gcm_encrypt(b"attack at dawn", &key, &iv, b"header").unwrap();
+
+        assert!(aes128_gcm_decrypt(&cipher, &key, &iv, b"header", &tag).is_ok());
+        assert_eq!(
+            aes128_gcm_decrypt(&cipher, &key, &iv, b"Header", &tag),
+            Err(CryptoError::InvalidTag)
+        );
+
+        cipher[0] ^= 1;
+        assert_eq!(
+            aes128_gcm_decrypt(&cipher, &key, &iv, b"header", &tag),
+            Err(CryptoError::InvalidTag)
+        );
+    }
+
+    #[test]
+    fn gcm_truncated_tag() {
+        let key = [0x42; 16];
+        let iv = [0x24; 12];
+        let (cipher, full_tag) = aes128_gcm_encrypt(b"attack at dawn", &key, &iv, b"").unwrap();
+        let (truncated_cipher, tag) =
+            aes128_gcm_encrypt_truncated(b"attack at dawn", &key, &iv, b"", 4).unwrap();
+
+        assert_eq!(cipher, truncated_cipher);
+        assert_eq!(tag, full_tag[..4]);
+        assert_eq!(
+            aes128_gcm_decrypt_truncated(&cipher, &key, &iv, b"", &tag).unwrap(),
+            b"attack at dawn"
+        );
+        assert!(aes128_gcm_decrypt_truncated(&cipher, &key, &iv, b"", &full_tag[1..5]).is_err());
+    }
+
+    #[test]
+    fn gcm_errors() {
+        let iv = [0x24; 12];
+        assert_eq!(
+            aes128_gcm_encrypt(b"plain", &[0; 15], &iv, b""),
+            Err(CryptoError::InvalidKeyLength(15))
+        );
+        assert_eq!(
+            aes128_gcm_decrypt(b"plain", &[0; 17], &iv, b"", &[0; 16]),
+            Err(CryptoError::InvalidKeyLength(17))
+        );
+        assert_eq!(
+            aes128_gcm_encrypt_truncated(b"plain", &[0; 16], &iv, b"", 17),
+            Err(CryptoError::InvalidTagLength(17))
+        );
+        assert_eq!(
+            aes128_gcm_decrypt_truncated(b"plain", &[0; 16], &iv, b"", &[]),
+            Err(CryptoError::InvalidTagLength(0))
+        );
+        assert_eq!(
+            gcm_encrypt(&Xtea::new(&[0; 16]).unwrap(), b"plain", &iv, b""),
+            Err(CryptoError::InvalidBlockSize(8))
+        );
+    }
+}
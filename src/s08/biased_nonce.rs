@@ -0,0 +1,184 @@
+use rug::{Integer, Rational};
+
+use crate::{
+    s06::dsa::{self, DsaPublic, Signature},
+    util::{integer::to_integer, lll::lll},
+};
+
+use super::ecdsa::{self, hash_message, EcdsaPublic};
+
+#[derive(Clone, Copy)]
+pub enum NonceBias {
+    LowBitsZero(u32),
+    HighBitsZero(u32),
+}
+
+impl NonceBias {
+    fn bits(&self) -> u32 {
+        match self {
+            NonceBias::LowBitsZero(bits) | NonceBias::HighBitsZero(bits) => *bits,
+        }
+    }
+}
+
+// Rewrites s = k^-1 (h + r x) as b = t x + u mod q, where b is the unknown
+// part of the nonce shifted by q / 2^(l+1) so that |b| <= q / 2^(l+1).
+fn hidden_number_sample(
+    h: &Integer,
+    signature: &Signature,
+    q: &Integer,
+    bias: NonceBias,
+) -> (Integer, Integer) {
+    let mut factor = signature.s.clone().invert(q).unwrap();
+    if let NonceBias::LowBitsZero(bits) = bias {
+        factor *= (Integer::from(1) << bits).invert(q).unwrap();
+    }
+    let center = Integer::from(q >> (bias.bits() + 1));
+    let t = Integer::from(&signature.r * &factor)
+        .div_rem_euc(q.clone())
+        .1;
+    let u = (Integer::from(h * &factor) - center)
+        .div_rem_euc(q.clone())
+        .1;
+    (t, u)
+}
+
+// Solves the hidden number problem for signatures (h, r, s) whose nonces are
+// all biased the same way. The lattice is spanned by q e_i, (t_1, ..., t_d,
+// 1/2^l, 0) and (u_1, ..., u_d, 0, q/2^l), scaled by 2^l, and contains the
+// short vector (2^l b_1, ..., 2^l b_d, x, q).
+pub fn biased_nonce_attack(
+    q: &Integer,
+    samples: &[(Integer, Signature)],
+    bias: NonceBias,
+    is_private_key: impl Fn(&Integer) -> bool,
+) -> Option<Integer> {
+    let d = samples.len();
+    let scale = Integer::from(1) << bias.bits();
+
+    let mut basis = Vec::with_capacity(d + 2);
+    for i in 0..d {
+        let mut row = vec![Integer::new(); d + 2];
+        row[i] = Integer::from(q * &scale);
+        basis.push(row);
+    }
+    let mut t_row = vec![Integer::new(); d + 2];
+    let mut u_row = vec![Integer::new(); d + 2];
+    for (i, (h, signature)) in samples.iter().enumerate() {
+        let (t, u) = hidden_number_sample(h, signature, q, bias);
+        t_row[i] = t * &scale;
+        u_row[i] = u * &scale;
+    }
+    t_row[d] = Integer::from(1);
+    u_row[d + 1] = q.clone();
+    basis.push(t_row);
+    basis.push(u_row);
+
+    let reduced = lll(&basis, &Rational::from((99, 100)));
+    reduced
+        .iter()
+        .filter(|row| row[d + 1] == *q || row[d + 1] == Integer::from(-q))
+        .map(|row| {
+            let x = if row[d + 1] == *q {
+                row[d].clone()
+            } else {
+                Integer::from(-&row[d])
+            };
+            x.div_rem_euc(q.clone()).1
+        })
+        .find(|x| is_private_key(x))
+}
+
+pub fn dsa_biased_nonce_attack(
+    public_key: &DsaPublic,
+    signatures: &[(Vec<u8>, Signature)],
+    bias: NonceBias,
+) -> Option<Integer> {
+    let samples: Vec<(Integer, Signature)> = signatures
+        .iter()
+        .map(|(message, signature)| (to_integer(message), signature.clone()))
+        .collect();
+    biased_nonce_attack(&public_key.parameters.q, &samples, bias, |x| {
+        dsa::is_private_key_for(x, public_key)
+    })
+}
+
+pub fn ecdsa_biased_nonce_attack(
+    public_key: &EcdsaPublic,
+    signatures: &[(Vec<u8>, Signature)],
+    bias: NonceBias,
+) -> Option<Integer> {
+    let n = &public_key.parameters.n;
+    let samples: Vec<(Integer, Signature)> = signatures
+        .iter()
+        .map(|(message, signature)| (hash_message(message, n), signature.clone()))
+        .collect();
+    biased_nonce_attack(n, &samples, bias, |x| {
+        ecdsa::is_private_key_for(x, public_key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        s06::dsa::{generate_dsa_key, DsaParameters},
+        s08::{ecdsa::generate_ecdsa_key, elliptic_curve::CurveParameters},
+        util::generators::generate_random_range,
+    };
+
+    use super::*;
+
+    fn biased_nonce(q: &Integer, bias: NonceBias) -> Integer {
+        let k = generate_random_range(&Integer::from(1), &(Integer::from(q >> bias.bits())));
+        match bias {
+            NonceBias::LowBitsZero(bits) => k << bits,
+            NonceBias::HighBitsZero(_) => k,
+        }
+    }
+
+    fn messages(count: usize) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|i| format!("message {i}").into_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn dsa_biased_nonce() {
+        let parameters = DsaParameters::default_parameters();
+        let (public_key, private_key) = generate_dsa_key(&parameters);
+
+        for bias in [NonceBias::LowBitsZero(16), NonceBias::HighBitsZero(16)] {
+            let signatures: Vec<(Vec<u8>, Signature)> = messages(14)
+                .into_iter()
+                .map(|message| {
+                    let k = biased_nonce(&parameters.q, bias);
+                    let signature = private_key.sign_with_chosen_k(&message, &k).unwrap();
+                    (message, signature)
+                })
+                .collect();
+
+            let x = dsa_biased_nonce_attack(&public_key, &signatures, bias);
+            assert_eq!(x, Some(private_key.x.clone()));
+        }
+    }
+
+    #[test]
+    fn s08e06() {
+        let parameters = CurveParameters::p256();
+        let (public_key, private_key) = generate_ecdsa_key(&parameters);
+        let bias = NonceBias::LowBitsZero(8);
+
+        let signatures: Vec<(Vec<u8>, Signature)> = messages(40)
+            .into_iter()
+            .map(|message| {
+                let k = biased_nonce(&parameters.n, bias);
+                let signature = private_key.sign_with_chosen_k(&message, &k).unwrap();
+                (message, signature)
+            })
+            .collect();
+
+        let x = ecdsa_biased_nonce_attack(&public_key, &signatures, bias);
+        assert_eq!(x, Some(private_key.x));
+    }
+}
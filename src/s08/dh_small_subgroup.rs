@@ -0,0 +1,137 @@
+use rug::Integer;
+
+use crate::{
+    s04::hmac::sha1_hmac,
+    util::{algebra::crt, generators::generate_random_range, integer::to_bytes},
+};
+
+const MESSAGE: &[u8] = b"crazy flamboyant for the rap enjoyment";
+
+pub struct MacBob {
+    p: Integer,
+    g: Integer,
+    x: Integer,
+}
+
+impl MacBob {
+    pub fn new(p: &Integer, g: &Integer, q: &Integer) -> MacBob {
+        MacBob {
+            p: p.clone(),
+            g: g.clone(),
+            x: generate_random_range(&Integer::from(1), q),
+        }
+    }
+
+    pub fn public_key(&self) -> Integer {
+        self.g.clone().pow_mod(&self.x, &self.p).unwrap()
+    }
+
+    // Bob does not check that h is in the subgroup generated by g.
+    pub fn respond(&self, h: &Integer) -> (Vec<u8>, [u8; 20]) {
+        let k = h.clone().pow_mod(&self.x, &self.p).unwrap();
+        (MESSAGE.to_vec(), sha1_hmac(&to_bytes(&k), MESSAGE))
+    }
+
+    pub fn is_private_key(&self, x: &Integer) -> bool {
+        &self.x == x
+    }
+}
+
+// Prime factors of n below bound which divide n only once.
+pub fn small_factors(n: &Integer, bound: u32) -> Vec<Integer> {
+    let mut remaining = n.clone();
+    let mut factors = Vec::new();
+    for r in 2..bound {
+        let mut multiplicity = 0;
+        while remaining.is_divisible_u(r) {
+            remaining /= r;
+            multiplicity += 1;
+        }
+        if multiplicity == 1 {
+            factors.push(Integer::from(r));
+        }
+    }
+    factors
+}
+
+// Finds an element of order r by raising random elements to (p - 1) / r.
+fn element_of_order(p: &Integer, r: &Integer) -> Integer {
+    let exponent = Integer::from(p - 1) / r;
+    loop {
+        let h = generate_random_range(&Integer::from(2), p)
+            .pow_mod(&exponent, p)
+            .unwrap();
+        if h != 1 {
+            return h;
+        }
+    }
+}
+
+fn brute_force_mac(
+    h: &Integer,
+    r: &Integer,
+    p: &Integer,
+    message: &[u8],
+    mac: &[u8; 20],
+) -> Integer {
+    let mut k = Integer::from(1);
+    let mut x = Integer::new();
+    while &x < r {
+        if sha1_hmac(&to_bytes(&k), message) == *mac {
+            return x;
+        }
+        k = (k * h) % p;
+        x += 1;
+    }
+    panic!("no residue matches the MAC");
+}
+
+// Learns x mod r for small factors r of (p - 1) / q until their product
+// exceeds q or the factors run out, and combines them into x mod prod r.
+pub fn recover_residues(bob: &MacBob, p: &Integer, q: &Integer) -> (Integer, Integer) {
+    let j = Integer::from(p - 1) / q;
+
+    let mut residues = Vec::new();
+    let mut modulus = Integer::from(1);
+    for r in small_factors(&j, 1 << 16) {
+        if modulus > *q {
+            break;
+        }
+        let h = element_of_order(p, &r);
+        let (message, mac) = bob.respond(&h);
+        let residue = brute_force_mac(&h, &r, p, &message, &mac);
+        println!("x = {residue} mod {r}");
+
+        modulus *= &r;
+        residues.push((residue, r));
+    }
+    crt(&residues)
+}
+
+pub fn subgroup_confinement_attack(bob: &MacBob, p: &Integer, q: &Integer) -> Integer {
+    let (x, modulus) = recover_residues(bob, p, q);
+    if modulus <= *q {
+        panic!("not enough small factors to recover the key");
+    }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::s05::dh::{get_smooth_g, get_smooth_p, get_smooth_q};
+
+    use super::*;
+
+    #[test]
+    fn s08e01() {
+        let p = get_smooth_p();
+        let g = get_smooth_g();
+        let q = get_smooth_q();
+        let bob = MacBob::new(&p, &g, &q);
+
+        let x = subgroup_confinement_attack(&bob, &p, &q);
+        assert!(bob.is_private_key(&x));
+        assert_eq!(g.pow_mod(&x, &p).unwrap(), bob.public_key());
+    }
+}
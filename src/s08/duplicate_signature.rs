@@ -0,0 +1,139 @@
+use rug::Integer;
+
+use crate::{
+    s05::rsa::RsaPublic,
+    s06::{dsa::Signature, rsa_signature::encode_signature},
+    util::{
+        algebra::{crt, pohlig_hellman},
+        generators::{generate_random_range, generate_smooth_prime},
+        integer::from_bytes,
+    },
+};
+
+use super::{
+    ecdsa::{hash_message, EcdsaPrivate, EcdsaPublic},
+    elliptic_curve::CurveParameters,
+};
+
+const FACTOR_BITS: usize = 16;
+
+// The verifier recomputes R = u1 * G + u2 * Q. For any new private key d',
+// the generator G' = (u1 + u2 * d')^-1 * R with Q' = d' * G' leads to the
+// same R, so the signature verifies under the new key as well.
+pub fn ecdsa_duplicate_key(
+    public_key: &EcdsaPublic,
+    message: &[u8],
+    signature: &Signature,
+) -> (EcdsaPublic, EcdsaPrivate) {
+    let parameters = &public_key.parameters;
+    let curve = &parameters.curve;
+    let n = &parameters.n;
+
+    let w = signature.s.clone().invert(n).unwrap();
+    let u1 = hash_message(message, n) * &w % n;
+    let u2 = Integer::from(&signature.r * &w) % n;
+    let r = curve.add(
+        &curve.scalar_mul(&parameters.g, &u1),
+        &curve.scalar_mul(&public_key.y, &u2),
+    );
+
+    loop {
+        let d = generate_random_range(&Integer::from(1), n);
+        let t = (Integer::from(&u2 * &d) + &u1) % n;
+        if let Ok(t_inv) = t.invert(n) {
+            let g = curve.scalar_mul(&r, &t_inv);
+            let y = curve.scalar_mul(&g, &d);
+            let parameters = CurveParameters {
+                g,
+                ..parameters.clone()
+            };
+            return (
+                EcdsaPublic {
+                    y,
+                    parameters: parameters.clone(),
+                },
+                EcdsaPrivate { x: d, parameters },
+            );
+        }
+    }
+}
+
+fn is_generator(g: &Integer, p: &Integer, factors: &[Integer]) -> bool {
+    let order = Integer::from(p - 1);
+    factors
+        .iter()
+        .all(|r| g.clone().pow_mod(&Integer::from(&order / r), p).unwrap() != 1)
+}
+
+// Picks N' = p * q where p - 1 and q - 1 are smooth and only share the factor
+// 2, so that e' with s^e' = pad(m) mod N' follows from discrete logs modulo
+// p and q.
+pub fn rsa_duplicate_key(public_key: &RsaPublic, message: &[u8], signature: &Integer) -> RsaPublic {
+    // N' needs the same length as N for the padding to match
+    let k = public_key.n.significant_digits::<u8>();
+    let bits = 8 * k;
+    let padded = from_bytes(&encode_signature(message, k).expect("modulus is too small"));
+
+    let (p, p_factors) = loop {
+        let (p, factors) = generate_smooth_prime(bits / 2, FACTOR_BITS);
+        if is_generator(signature, &p, &factors) {
+            break (p, factors);
+        }
+    };
+    let e_p = pohlig_hellman(signature, &padded, &p, &p_factors).unwrap();
+
+    loop {
+        let (q, q_factors) = generate_smooth_prime(bits - bits / 2, FACTOR_BITS);
+        if q_factors[1..].iter().any(|r| p_factors.contains(r))
+            || !is_generator(signature, &q, &q_factors)
+        {
+            continue;
+        }
+        let e_q = pohlig_hellman(signature, &padded, &q, &q_factors).unwrap();
+
+        // e' mod 2 is fixed by both discrete logs
+        if e_p.is_odd() != e_q.is_odd() {
+            continue;
+        }
+        let half = Integer::from(&q - 1) / 2;
+        let (e, _) = crt(&[(e_p, Integer::from(&p - 1)), (e_q % &half, half)]);
+        return RsaPublic { e, n: p * q };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        s05::rsa::rsa_keygen,
+        s08::ecdsa::{generate_ecdsa_key, is_private_key_for},
+    };
+
+    use super::*;
+
+    #[test]
+    fn ecdsa_duplicate_signature() {
+        let parameters = CurveParameters::p256();
+        let (public_key, private_key) = generate_ecdsa_key(&parameters);
+        let message = b"hi mom";
+        let signature = private_key.sign(message);
+
+        let (new_public, new_private) = ecdsa_duplicate_key(&public_key, message, &signature);
+        assert_ne!(new_public.y, public_key.y);
+        assert!(is_private_key_for(&new_private.x, &new_public));
+        assert!(new_public.verify(message, &signature));
+        assert!(!new_public.verify(b"hi dad", &signature));
+    }
+
+    #[test]
+    fn rsa_duplicate_signature() {
+        let (public_key, private_key) = rsa_keygen(1024);
+        let message = b"hi mom";
+        let signature = private_key.sign_pkcs1_5(message).unwrap();
+
+        let new_public = rsa_duplicate_key(&public_key, message, &signature);
+        assert_ne!(new_public.n, public_key.n);
+        assert!(new_public.verify_pkcs1_5(message, &signature));
+        assert!(!new_public.verify_pkcs1_5(b"hi dad", &signature));
+    }
+}
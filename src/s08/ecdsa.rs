@@ -0,0 +1,247 @@
+use rug::Integer;
+
+use crate::{
+    s06::{dsa::Signature, dsa_repeated_nonce::MessageAndSignature},
+    util::{generators::generate_random_range, integer::to_integer},
+};
+
+use super::elliptic_curve::{CurveParameters, Point};
+
+// The SHA-1 hash, truncated to the bit length of n as in FIPS 186-4.
+pub fn hash_message(message: &[u8], n: &Integer) -> Integer {
+    let h_m = to_integer(message);
+    let excess = 160u32.saturating_sub(n.significant_bits());
+    h_m >> excess
+}
+
+fn reduce(value: Integer, n: &Integer) -> Integer {
+    value.div_rem_euc(n.clone()).1
+}
+
+pub struct EcdsaPublic {
+    pub y: Point,
+    pub parameters: CurveParameters,
+}
+
+impl EcdsaPublic {
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        let n = &self.parameters.n;
+        let h_m = hash_message(message, n);
+
+        let r = &signature.r;
+        let s = &signature.s;
+        let valid_input = (0 < *r && r < n) && (0 < *s && s < n);
+        if !valid_input {
+            return false;
+        }
+
+        let w = s.clone().invert(n).unwrap();
+        let u1 = h_m * &w % n;
+        let u2 = Integer::from(r * &w) % n;
+
+        let curve = &self.parameters.curve;
+        let point = curve.add(
+            &curve.scalar_mul(&self.parameters.g, &u1),
+            &curve.scalar_mul(&self.y, &u2),
+        );
+        match point.x() {
+            Some(x) => Integer::from(x % n) == *r,
+            None => false,
+        }
+    }
+}
+
+pub struct EcdsaPrivate {
+    pub x: Integer,
+    pub parameters: CurveParameters,
+}
+
+impl EcdsaPrivate {
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        let h_m = hash_message(message, &self.parameters.n);
+
+        loop {
+            let k = generate_random_range(&Integer::from(1), &self.parameters.n);
+            if let Some(sig) = self.sign_with_k(&k, &h_m) {
+                return sig;
+            }
+        }
+    }
+
+    pub fn sign_with_chosen_k(&self, message: &[u8], k: &Integer) -> Option<Signature> {
+        let h_m = hash_message(message, &self.parameters.n);
+        self.sign_with_k(k, &h_m)
+    }
+
+    fn sign_with_k(&self, k: &Integer, h_m: &Integer) -> Option<Signature> {
+        let n = &self.parameters.n;
+        let point = self.parameters.curve.scalar_mul(&self.parameters.g, k);
+        let r = Integer::from(point.x()? % n);
+        if r == 0 {
+            return None;
+        }
+
+        let k_inv = k.clone().invert(n).ok()?;
+        let s = reduce(k_inv * Integer::from(h_m + &r * &self.x), n);
+
+        if s == 0 {
+            return None;
+        };
+        Some(Signature { r, s })
+    }
+}
+
+pub fn generate_ecdsa_key(parameters: &CurveParameters) -> (EcdsaPublic, EcdsaPrivate) {
+    let x = generate_random_range(&Integer::from(1), &parameters.n);
+    let y = parameters.curve.scalar_mul(&parameters.g, &x);
+    (
+        EcdsaPublic {
+            y,
+            parameters: parameters.clone(),
+        },
+        EcdsaPrivate {
+            x,
+            parameters: parameters.clone(),
+        },
+    )
+}
+
+pub fn known_k_attack(
+    parameters: &CurveParameters,
+    message: &[u8],
+    signature: &Signature,
+    k: &Integer,
+) -> Integer {
+    let n = &parameters.n;
+    let h_m = hash_message(message, n);
+    let r_inv = signature.r.clone().invert(n).unwrap();
+    reduce(r_inv * (&signature.s * k - h_m), n)
+}
+
+pub fn is_private_key_for(x: &Integer, pubkey: &EcdsaPublic) -> bool {
+    pubkey.parameters.curve.scalar_mul(&pubkey.parameters.g, x) == pubkey.y
+}
+
+// Two signatures sharing r were made with the same nonce k.
+pub fn find_k(
+    msg1: &MessageAndSignature,
+    msg2: &MessageAndSignature,
+    parameters: &CurveParameters,
+) -> Integer {
+    let n = &parameters.n;
+    let m1 = hash_message(msg1.message.as_bytes(), n);
+    let m2 = hash_message(msg2.message.as_bytes(), n);
+    let s1 = &msg1.signature.s;
+    let s2 = &msg2.signature.s;
+
+    let inverse_denom = reduce(Integer::from(s1 - s2), n).invert(n).unwrap();
+    reduce((m1 - m2) * inverse_denom, n)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::s06::dsa_repeated_nonce::find_duplicates;
+
+    use super::*;
+
+    fn from_hex(hex: &str) -> Integer {
+        Integer::from_str_radix(hex, 16).unwrap()
+    }
+
+    #[test]
+    fn ecdsa_p256_sha1() {
+        // RFC 6979, A.2.5, with SHA-1 and message "sample"
+        let parameters = CurveParameters::p256();
+        let private = EcdsaPrivate {
+            x: from_hex("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721"),
+            parameters: parameters.clone(),
+        };
+        let public = EcdsaPublic {
+            y: parameters.curve.scalar_mul(&parameters.g, &private.x),
+            parameters: parameters.clone(),
+        };
+        let k = from_hex("882905f1227fd620fbf2abf21244f0ba83d0dc3a9103dbbee43a1fb858109db4");
+
+        let signature = private.sign_with_chosen_k(b"sample", &k).unwrap();
+        assert_eq!(
+            signature.r,
+            from_hex("61340c88c3aaebeb4f6d667f672ca9759a6ccaa9fa8811313039ee4a35471d32")
+        );
+        assert_eq!(
+            signature.s,
+            from_hex("6d7f147dac089441bb2e2fe8f7a3fa264b9c475098fdcf6e00d7c996e1b8b7eb")
+        );
+        assert!(public.verify(b"sample", &signature));
+        assert!(!public.verify(b"test", &signature));
+    }
+
+    #[test]
+    fn sign_verify() {
+        for parameters in [CurveParameters::secp256k1(), CurveParameters::p256()] {
+            let (ecdsa_public, ecdsa_private) = generate_ecdsa_key(&parameters);
+            assert!(is_private_key_for(&ecdsa_private.x, &ecdsa_public));
+
+            let signature = ecdsa_private.sign(b"abc");
+            assert!(ecdsa_public.verify(b"abc", &signature));
+            assert!(!ecdsa_public.verify(b"abd", &signature));
+        }
+    }
+
+    #[test]
+    fn known_k_attack_test() {
+        let parameters = CurveParameters::secp256k1();
+        let (ecdsa_public, ecdsa_private) = generate_ecdsa_key(&parameters);
+
+        let message = "abc";
+        let k = generate_random_range(&Integer::from(1), &parameters.n);
+        let signature = ecdsa_private
+            .sign_with_chosen_k(message.as_bytes(), &k)
+            .unwrap();
+
+        let x_try = known_k_attack(&parameters, message.as_bytes(), &signature, &k);
+        assert_eq!(x_try, ecdsa_private.x);
+        assert!(is_private_key_for(&x_try, &ecdsa_public));
+    }
+
+    #[test]
+    fn repeated_nonce_attack() {
+        let parameters = CurveParameters::p256();
+        let (ecdsa_public, ecdsa_private) = generate_ecdsa_key(&parameters);
+
+        let k = generate_random_range(&Integer::from(1), &parameters.n);
+        let mut ms: Vec<MessageAndSignature> = [
+            "Listen for me, you better listen for me now. ",
+            "Pure black people mon is all I mon know. ",
+        ]
+        .iter()
+        .map(|message| MessageAndSignature {
+            message: message.to_string(),
+            signature: ecdsa_private.sign(message.as_bytes()),
+        })
+        .collect();
+        for message in [
+            "When me rockin' the microphone me rock on steady, ",
+            "Yes a Daddy me Snow me are de article dan. ",
+        ] {
+            ms.push(MessageAndSignature {
+                message: message.to_string(),
+                signature: ecdsa_private
+                    .sign_with_chosen_k(message.as_bytes(), &k)
+                    .unwrap(),
+            });
+        }
+
+        let duplicate_result = find_duplicates(&mut ms).unwrap();
+        let k_try = find_k(duplicate_result.0, duplicate_result.1, &parameters);
+        assert_eq!(k_try, k);
+
+        let x_try = known_k_attack(
+            &parameters,
+            duplicate_result.0.message.as_bytes(),
+            &duplicate_result.0.signature,
+            &k_try,
+        );
+        assert!(is_private_key_for(&x_try, &ecdsa_public));
+    }
+}
@@ -0,0 +1,306 @@
+use rug::Integer;
+
+use crate::util::{algebra::sqrt_mod, generators::generate_random_range, group::Group};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Point {
+    Infinity,
+    Affine(Integer, Integer),
+}
+
+impl Point {
+    pub fn new(x: &Integer, y: &Integer) -> Point {
+        Point::Affine(x.clone(), y.clone())
+    }
+
+    pub fn x(&self) -> Option<&Integer> {
+        match self {
+            Point::Infinity => None,
+            Point::Affine(x, _) => Some(x),
+        }
+    }
+}
+
+// y^2 = x^3 + ax + b over GF(p)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct WeierstrassCurve {
+    pub p: Integer,
+    pub a: Integer,
+    pub b: Integer,
+}
+
+impl WeierstrassCurve {
+    pub fn new(p: &Integer, a: &Integer, b: &Integer) -> WeierstrassCurve {
+        WeierstrassCurve {
+            p: p.clone(),
+            a: a.clone(),
+            b: b.clone(),
+        }
+    }
+
+    fn reduce(&self, value: Integer) -> Integer {
+        value.div_rem_euc(self.p.clone()).1
+    }
+
+    pub fn is_on_curve(&self, point: &Point) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine(x, y) => {
+                let lhs = Integer::from(y * y);
+                let rhs = Integer::from(x * x) * x + &self.a * x + &self.b;
+                self.reduce(lhs - rhs) == 0
+            }
+        }
+    }
+
+    pub fn negate(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => Point::Affine(x.clone(), self.reduce(-y.clone())),
+        }
+    }
+
+    pub fn add(&self, p1: &Point, p2: &Point) -> Point {
+        let (x1, y1, x2, y2) = match (p1, p2) {
+            (Point::Infinity, _) => return p2.clone(),
+            (_, Point::Infinity) => return p1.clone(),
+            (Point::Affine(x1, y1), Point::Affine(x2, y2)) => (x1, y1, x2, y2),
+        };
+        if *p1 == self.negate(p2) {
+            return Point::Infinity;
+        }
+
+        let slope = if p1 == p2 {
+            let numerator = Integer::from(x1 * x1) * 3 + &self.a;
+            let denominator = Integer::from(y1 * 2).invert(&self.p).unwrap();
+            self.reduce(numerator * denominator)
+        } else {
+            let numerator = Integer::from(y2 - y1);
+            let denominator = self.reduce(Integer::from(x2 - x1)).invert(&self.p).unwrap();
+            self.reduce(numerator * denominator)
+        };
+
+        let x3 = self.reduce(Integer::from(&slope * &slope) - x1 - x2);
+        let y3 = self.reduce(slope * Integer::from(x1 - &x3) - y1);
+        Point::Affine(x3, y3)
+    }
+
+    pub fn double(&self, point: &Point) -> Point {
+        self.add(point, point)
+    }
+
+    pub fn scalar_mul(&self, point: &Point, k: &Integer) -> Point {
+        let base = if *k < 0 {
+            self.negate(point)
+        } else {
+            point.clone()
+        };
+        let k = k.clone().abs();
+
+        let mut result = Point::Infinity;
+        for i in (0..k.significant_bits()).rev() {
+            result = self.double(&result);
+            if k.get_bit(i) {
+                result = self.add(&result, &base);
+            }
+        }
+        result
+    }
+
+    pub fn random_point(&self) -> Point {
+        loop {
+            let x = generate_random_range(&Integer::new(), &self.p);
+            let rhs = Integer::from(&x * &x) * &x + &self.a * &x + &self.b;
+            if let Some(y) = sqrt_mod(&rhs, &self.p) {
+                return Point::Affine(x, y);
+            }
+        }
+    }
+
+    // True if the order of the point divides n.
+    pub fn order_divides(&self, point: &Point, n: &Integer) -> bool {
+        self.scalar_mul(point, n) == Point::Infinity
+    }
+}
+
+impl Group for WeierstrassCurve {
+    type Element = Point;
+
+    fn identity(&self) -> Point {
+        Point::Infinity
+    }
+
+    fn operate(&self, a: &Point, b: &Point) -> Point {
+        self.add(a, b)
+    }
+
+    fn inverse(&self, a: &Point) -> Point {
+        self.negate(a)
+    }
+
+    fn fingerprint(&self, a: &Point) -> u64 {
+        a.x().map_or(0, |x| x.to_u64_wrapping())
+    }
+
+    fn pow(&self, base: &Point, exponent: &Integer) -> Point {
+        self.scalar_mul(base, exponent)
+    }
+}
+
+// A curve together with a base point g of prime order n and the cofactor h.
+#[derive(Clone)]
+pub struct CurveParameters {
+    pub curve: WeierstrassCurve,
+    pub g: Point,
+    pub n: Integer,
+    pub h: Integer,
+}
+
+fn from_hex(hex: &str) -> Integer {
+    Integer::from_str_radix(hex, 16).unwrap()
+}
+
+impl CurveParameters {
+    pub fn secp256k1() -> Self {
+        CurveParameters {
+            curve: WeierstrassCurve::new(
+                &from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+                &Integer::from(0),
+                &Integer::from(7),
+            ),
+            g: Point::new(
+                &from_hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+                &from_hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+            ),
+            n: from_hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+            h: Integer::from(1),
+        }
+    }
+
+    pub fn p256() -> Self {
+        let p = from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        CurveParameters {
+            curve: WeierstrassCurve::new(
+                &p,
+                &Integer::from(&p - 3),
+                &from_hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+            ),
+            g: Point::new(
+                &from_hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+                &from_hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            ),
+            n: from_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+            h: Integer::from(1),
+        }
+    }
+
+    // Checks that a received public key is a point of order n on the curve.
+    pub fn is_valid_public_key(&self, point: &Point) -> bool {
+        *point != Point::Infinity
+            && self.curve.is_on_curve(point)
+            && self.curve.order_divides(point, &self.n)
+    }
+}
+
+pub fn generate_ecdh_key(parameters: &CurveParameters) -> (Point, Integer) {
+    let a = generate_random_range(&Integer::from(1), &parameters.n);
+    (parameters.curve.scalar_mul(&parameters.g, &a), a)
+}
+
+pub fn generate_ecdh_session_key(
+    b_public: &Point,
+    a_private: &Integer,
+    curve: &WeierstrassCurve,
+) -> Point {
+    curve.scalar_mul(b_public, a_private)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn secp256k1_vectors() {
+        let parameters = CurveParameters::secp256k1();
+        let curve = &parameters.curve;
+        let g = &parameters.g;
+        assert!(curve.is_on_curve(g));
+        assert!(parameters.is_valid_public_key(g));
+
+        let g2 = Point::new(
+            &from_hex("c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"),
+            &from_hex("1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a"),
+        );
+        let g3 = Point::new(
+            &from_hex("f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"),
+            &from_hex("388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672"),
+        );
+        assert_eq!(curve.double(g), g2);
+        assert_eq!(curve.add(&g2, g), g3);
+        assert_eq!(curve.scalar_mul(g, &Integer::from(3)), g3);
+        assert_eq!(curve.scalar_mul(g, &Integer::from(-3)), curve.negate(&g3));
+        assert_eq!(curve.add(&g3, &curve.negate(&g3)), Point::Infinity);
+        assert_eq!(
+            curve.scalar_mul(g, &Integer::from(&parameters.n - 1)),
+            curve.negate(g)
+        );
+        assert!(curve.order_divides(g, &parameters.n));
+
+        let off_curve = Point::new(&Integer::from(1), &Integer::from(1));
+        assert!(!curve.is_on_curve(&off_curve));
+        assert!(!parameters.is_valid_public_key(&off_curve));
+        assert!(!parameters.is_valid_public_key(&Point::Infinity));
+
+        let random = curve.random_point();
+        assert!(curve.is_on_curve(&random));
+        assert!(curve.order_divides(&random, &parameters.n));
+    }
+
+    #[test]
+    fn p256_vectors() {
+        let parameters = CurveParameters::p256();
+        let curve = &parameters.curve;
+        assert!(parameters.is_valid_public_key(&parameters.g));
+
+        let g2 = Point::new(
+            &from_hex("7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978"),
+            &from_hex("07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1"),
+        );
+        assert_eq!(curve.double(&parameters.g), g2);
+
+        // RFC 5903, section 8.1
+        let i = from_hex("c88f01f510d9ac3f70a292daa2316de544e9aab8afe84049c62a9c57862d1433");
+        let g_i = Point::new(
+            &from_hex("dad0b65394221cf9b051e1feca5787d098dfe637fc90b9ef945d0c3772581180"),
+            &from_hex("5271a0461cdb8252d61f1c456fa3e59ab1f45b33accf5f58389e0577b8990bb3"),
+        );
+        let r = from_hex("c6ef9c5d78ae012a011164acb397ce2088685d8f06bf9be0b283ab46476bee53");
+        let g_r = Point::new(
+            &from_hex("d12dfb5289c8d4f81208b70270398c342296970a0bccb74c736fc7554494bf63"),
+            &from_hex("56fbf3ca366cc23e8157854c13c58d6aac23f046ada30f8353e74f33039872ab"),
+        );
+        assert_eq!(curve.scalar_mul(&parameters.g, &i), g_i);
+        assert_eq!(curve.scalar_mul(&parameters.g, &r), g_r);
+
+        let shared = generate_ecdh_session_key(&g_r, &i, curve);
+        assert_eq!(shared, generate_ecdh_session_key(&g_i, &r, curve));
+        assert_eq!(
+            shared.x().unwrap().to_string_radix(16),
+            "d6840f6b42f6edafd13116e0e12565202fef8e9ece7dce03812464d04b9442de"
+        );
+    }
+
+    #[test]
+    fn ecdh() {
+        for parameters in [CurveParameters::secp256k1(), CurveParameters::p256()] {
+            let (a_public, a_private) = generate_ecdh_key(&parameters);
+            let (b_public, b_private) = generate_ecdh_key(&parameters);
+            assert!(parameters.is_valid_public_key(&a_public));
+
+            let s_for_a = generate_ecdh_session_key(&b_public, &a_private, &parameters.curve);
+            let s_for_b = generate_ecdh_session_key(&a_public, &b_private, &parameters.curve);
+            assert_eq!(s_for_a, s_for_b);
+        }
+    }
+}
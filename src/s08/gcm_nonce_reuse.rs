@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+
+use crate::util::{gf128::Gf128, gf128_polynomial::Gf128Polynomial, xor::xor};
+
+use super::aes_gcm::{aes128_gcm_decrypt, aes128_gcm_encrypt, ghash, ghash_coefficients};
+
+#[derive(Clone)]
+pub struct GcmMessage {
+    pub aad: Vec<u8>,
+    pub cipher: Vec<u8>,
+    pub tag: [u8; 16],
+}
+
+pub struct GcmNonceReuseOracle {
+    key: Vec<u8>,
+    nonce: Vec<u8>,
+}
+
+impl GcmNonceReuseOracle {
+    pub fn new(key: &[u8], nonce: &[u8]) -> GcmNonceReuseOracle {
+        GcmNonceReuseOracle {
+            key: key.to_vec(),
+            nonce: nonce.to_vec(),
+        }
+    }
+
+    pub fn encrypt(&self, plain: &[u8], aad: &[u8]) -> GcmMessage {
+        let (cipher, tag) = aes128_gcm_encrypt(plain, &self.key, &self.nonce, aad).unwrap();
+        GcmMessage {
+            aad: aad.to_vec(),
+            cipher,
+            tag,
+        }
+    }
+
+    pub fn decrypt(&self, message: &GcmMessage) -> Option<Vec<u8>> {
+        aes128_gcm_decrypt(
+            &message.cipher,
+            &self.key,
+            &self.nonce,
+            &message.aad,
+            &message.tag,
+        )
+        .ok()
+    }
+}
+
+// tag = c_1 h^n + ... + c_n h + s, so h is a root of this polynomial plus s.
+fn tag_polynomial(message: &GcmMessage) -> Gf128Polynomial {
+    let mut coefficients = ghash_coefficients(&message.aad, &message.cipher);
+    coefficients.push(Gf128::from_block(&message.tag));
+    coefficients.reverse();
+    Gf128Polynomial::new(&coefficients)
+}
+
+pub fn recover_hash_key_candidates(messages: &[GcmMessage]) -> Vec<Gf128> {
+    if messages.len() < 2 {
+        panic!("need at least two messages with the same nonce");
+    }
+
+    let first = tag_polynomial(&messages[0]);
+    let mut candidates: Option<HashSet<Gf128>> = None;
+
+    for message in &messages[1..] {
+        let difference = &first - &tag_polynomial(message);
+        let roots: HashSet<Gf128> = difference.roots().into_iter().collect();
+        candidates = Some(match candidates {
+            None => roots,
+            Some(previous) => previous.intersection(&roots).cloned().collect(),
+        });
+    }
+
+    candidates.unwrap().into_iter().collect()
+}
+
+pub fn forge_tag(known: &GcmMessage, h: Gf128, aad: &[u8], cipher: &[u8]) -> [u8; 16] {
+    let mask = xor(&known.tag, &ghash(h, &known.aad, &known.cipher).to_block());
+    xor(&ghash(h, aad, cipher).to_block(), &mask)
+        .try_into()
+        .unwrap()
+}
+
+pub fn forge_message(
+    oracle: &GcmNonceReuseOracle,
+    messages: &[GcmMessage],
+    aad: &[u8],
+    cipher: &[u8],
+) -> Option<(Gf128, GcmMessage)> {
+    for h in recover_hash_key_candidates(messages) {
+        let forgery = GcmMessage {
+            aad: aad.to_vec(),
+            cipher: cipher.to_vec(),
+            tag: forge_tag(&messages[0], h, aad, cipher),
+        };
+        if oracle.decrypt(&forgery).is_some() {
+            return Some((h, forgery));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+
+    use aes::{cipher::KeyInit, Aes128};
+
+    use crate::{s08::aes_gcm::gcm_hash_key, util::generators::generate_aes_key};
+
+    use super::*;
+
+    #[test]
+    fn s08e07() {
+        let key = generate_aes_key();
+        let oracle = GcmNonceReuseOracle::new(&key, &generate_aes_key()[..12]);
+
+        let messages = vec![
+            oracle.encrypt(
+                b"transfer 100 to account 1234 from account 5678",
+                b"user=alice",
+            ),
+            oracle.encrypt(
+                b"transfer 250 to account 8765 from account 5678",
+                b"user=alice",
+            ),
+            oracle.encrypt(b"logout", b"user=alice;session=17"),
+        ];
+
+        let candidates = recover_hash_key_candidates(&messages);
+        let h = gcm_hash_key(&Aes128::new_from_slice(&key).unwrap()).unwrap();
+        assert!(candidates.contains(&h));
+
+        let mut cipher = messages[0].cipher.clone();
+        let flip = xor(b"100", b"999");
+        for (c, f) in cipher[9..12].iter_mut().zip(flip) {
+            *c ^= f;
+        }
+
+        let (recovered_h, forgery) =
+            forge_message(&oracle, &messages, b"user=mallory", &cipher).expect("forgery failed");
+        assert_eq!(recovered_h, h);
+
+        let plain = oracle.decrypt(&forgery).unwrap();
+        assert_eq!(plain, b"transfer 999 to account 1234 from account 5678");
+    }
+}
@@ -0,0 +1,213 @@
+use std::cmp::min;
+
+use crate::util::{algebra::Gf2Matrix, gf128::Gf128};
+
+use super::aes_gcm::{aes128_gcm_decrypt_truncated, aes128_gcm_encrypt_truncated};
+
+pub struct GcmTruncatedMacOracle {
+    key: Vec<u8>,
+    nonce: Vec<u8>,
+    tag_len: usize,
+    tries: usize,
+}
+
+impl GcmTruncatedMacOracle {
+    pub fn new(key: &[u8], nonce: &[u8], tag_len: usize) -> GcmTruncatedMacOracle {
+        GcmTruncatedMacOracle {
+            key: key.to_vec(),
+            nonce: nonce.to_vec(),
+            tag_len,
+            tries: 0,
+        }
+    }
+
+    pub fn encrypt(&self, plain: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        aes128_gcm_encrypt_truncated(plain, &self.key, &self.nonce, &[], self.tag_len).unwrap()
+    }
+
+    pub fn is_valid(&mut self, cipher: &[u8], tag: &[u8]) -> bool {
+        self.tries += 1;
+        aes128_gcm_decrypt_truncated(cipher, &self.key, &self.nonce, &[], tag).is_ok()
+    }
+
+    pub fn print_tries(&self) {
+        println!("Took {} oracle invocations", self.tries);
+    }
+}
+
+// The error polynomial sum_j e_j h^(2^j) is linear in h. Column c of the
+// returned matrix is the error for h = x^c.
+fn error_matrix(errors: &[Gf128]) -> Gf2Matrix {
+    let mut matrix = Gf2Matrix::new(128, 128);
+    for col in 0..128 {
+        let mut power = Gf128(1 << col);
+        let mut error = Gf128::zero();
+        for e in errors {
+            power = power.square();
+            error += *e * power;
+        }
+        for row in 0..128 {
+            matrix.set(row, col, (error.0 >> row) & 1 == 1);
+        }
+    }
+    matrix
+}
+
+// Maps the bits of all error blocks to the first `zero_rows` rows of
+// error_matrix(errors) * basis.
+fn dependency_matrix(basis: &Gf2Matrix, zero_rows: usize, doubling_blocks: usize) -> Gf2Matrix {
+    let cols = basis.cols();
+    let mut matrix = Gf2Matrix::new(zero_rows * cols, doubling_blocks * 128);
+    for col in 0..cols {
+        let mut power = bits_to_field(&basis.column(col));
+        for j in 0..doubling_blocks {
+            power = power.square();
+            let mut product = power;
+            for bit in 0..128 {
+                for row in 0..zero_rows {
+                    if (product.0 >> row) & 1 == 1 {
+                        matrix.set(row * cols + col, j * 128 + bit, true);
+                    }
+                }
+                product *= Gf128(2);
+            }
+        }
+    }
+    matrix
+}
+
+fn bits_to_field(bits: &[bool]) -> Gf128 {
+    Gf128(
+        bits.iter()
+            .enumerate()
+            .fold(0, |acc, (i, bit)| acc | ((*bit as u128) << i)),
+    )
+}
+
+fn random_kernel_vector(kernel: &[Vec<bool>], len: usize) -> Vec<bool> {
+    let mut vector = vec![false; len];
+    for basis_vector in kernel {
+        if rand::random() {
+            for (bit, basis_bit) in vector.iter_mut().zip(basis_vector) {
+                *bit ^= basis_bit;
+            }
+        }
+    }
+    vector
+}
+
+fn apply_errors(cipher: &[u8], errors: &[Gf128]) -> Vec<u8> {
+    let mut forged = cipher.to_vec();
+    let blocks = cipher.len() / 16;
+    for (j, error) in errors.iter().enumerate() {
+        // the last ciphertext block is multiplied with h^2, the length block with h
+        let block = blocks + 1 - (1 << (j + 1));
+        for (byte, error_byte) in forged[block * 16..(block + 1) * 16]
+            .iter_mut()
+            .zip(error.to_block())
+        {
+            *byte ^= error_byte;
+        }
+    }
+    forged
+}
+
+pub fn truncated_mac_attack(
+    oracle: &mut GcmTruncatedMacOracle,
+    cipher: &[u8],
+    tag: &[u8],
+    doubling_blocks: usize,
+) -> Option<Gf128> {
+    if !cipher.len().is_multiple_of(16) || cipher.len() / 16 + 1 < 1 << doubling_blocks {
+        panic!("ciphertext must consist of at least 2^n - 1 full blocks");
+    }
+    let tag_bits = tag.len() * 8;
+    let unknowns = doubling_blocks * 128;
+
+    let mut equations = Gf2Matrix::new(0, 128);
+
+    loop {
+        let basis = if equations.rows() == 0 {
+            Gf2Matrix::identity(128)
+        } else {
+            Gf2Matrix::from_columns(128, &equations.kernel())
+        };
+
+        match basis.cols() {
+            0 => return None,
+            1 => return Some(bits_to_field(&basis.column(0))),
+            _ => {}
+        }
+
+        let zero_rows = min(tag_bits - 1, (unknowns - 1) / basis.cols());
+        println!(
+            " {} equations, forcing {zero_rows} tag bits to zero...",
+            equations.rows()
+        );
+        let kernel = dependency_matrix(&basis, zero_rows, doubling_blocks).kernel();
+
+        loop {
+            let vector = random_kernel_vector(&kernel, unknowns);
+            let errors: Vec<Gf128> = vector.chunks(128).map(bits_to_field).collect();
+            if errors.iter().all(|e| e.is_zero()) {
+                continue;
+            }
+
+            if !oracle.is_valid(&apply_errors(cipher, &errors), tag) {
+                continue;
+            }
+
+            let error = error_matrix(&errors);
+            let restricted = error.mul(&basis);
+            let mut new_equations = false;
+            for row in zero_rows..tag_bits {
+                if !restricted.is_zero_row(row) {
+                    equations.push_row(&error.row(row));
+                    new_equations = true;
+                }
+            }
+            if new_equations {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use aes::{cipher::KeyInit, Aes128};
+    use rand::RngCore;
+
+    use crate::{s08::aes_gcm::gcm_hash_key, util::generators::generate_aes_key};
+
+    use super::*;
+
+    fn attack(tag_len: usize, doubling_blocks: usize) {
+        let key = generate_aes_key();
+        let mut oracle = GcmTruncatedMacOracle::new(&key, &generate_aes_key()[..12], tag_len);
+
+        let mut plain = vec![0; ((1 << doubling_blocks) - 1) * 16];
+        rand::thread_rng().fill_bytes(&mut plain);
+        let (cipher, tag) = oracle.encrypt(&plain);
+
+        let h = truncated_mac_attack(&mut oracle, &cipher, &tag, doubling_blocks).unwrap();
+        oracle.print_tries();
+
+        assert_eq!(
+            h,
+            gcm_hash_key(&Aes128::new_from_slice(&key).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn s08e08() {
+        attack(2, 9);
+    }
+
+    #[test]
+    #[ignore]
+    fn s08e08_32_bit_tag() {
+        attack(4, 17);
+    }
+}
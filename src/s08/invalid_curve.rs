@@ -0,0 +1,190 @@
+use rug::Integer;
+
+use crate::{
+    s04::hmac::sha1_hmac,
+    util::{algebra::crt, generators::generate_random_range, integer::to_bytes},
+};
+
+use super::{
+    dh_small_subgroup::small_factors,
+    elliptic_curve::{CurveParameters, Point, WeierstrassCurve},
+};
+
+const MESSAGE: &[u8] = b"crazy flamboyant for the rap enjoyment";
+
+fn get_weak_p() -> Integer {
+    Integer::from_str_radix("233970423115425145524320034830162017933", 10).unwrap()
+}
+
+fn get_weak_a() -> Integer {
+    Integer::from(-95051)
+}
+
+// y^2 = x^3 - 95051x + 11279326, whose base point has a 125 bit prime order
+pub fn get_weak_curve_parameters() -> CurveParameters {
+    CurveParameters {
+        curve: WeierstrassCurve::new(&get_weak_p(), &get_weak_a(), &Integer::from(11279326)),
+        g: Point::new(
+            &Integer::from(182),
+            &Integer::from_str_radix("85518893674295321206118380980485522083", 10).unwrap(),
+        ),
+        n: Integer::from_str_radix("29246302889428143187362802287225875743", 10).unwrap(),
+        h: Integer::from(8),
+    }
+}
+
+// Curves sharing p and a with the weak curve, so the same addition formulas
+// apply, together with their orders.
+pub fn get_invalid_curves() -> Vec<(WeierstrassCurve, Integer)> {
+    [
+        (210, "233970423115425145550826547352470124412"),
+        (504, "233970423115425145544350131142039591210"),
+        (727, "233970423115425145545378039958152057148"),
+    ]
+    .iter()
+    .map(|(b, order)| {
+        (
+            WeierstrassCurve::new(&get_weak_p(), &get_weak_a(), &Integer::from(*b)),
+            Integer::from_str_radix(order, 10).unwrap(),
+        )
+    })
+    .collect()
+}
+
+fn point_to_key(point: &Point) -> Vec<u8> {
+    match point {
+        Point::Infinity => Vec::new(),
+        Point::Affine(x, y) => [to_bytes(x), to_bytes(y)].concat(),
+    }
+}
+
+pub struct EcdhBob {
+    parameters: CurveParameters,
+    x: Integer,
+}
+
+impl EcdhBob {
+    pub fn new(parameters: &CurveParameters) -> EcdhBob {
+        EcdhBob {
+            parameters: parameters.clone(),
+            x: generate_random_range(&Integer::from(1), &parameters.n),
+        }
+    }
+
+    pub fn public_key(&self) -> Point {
+        self.parameters
+            .curve
+            .scalar_mul(&self.parameters.g, &self.x)
+    }
+
+    // Bob does not check that h is on his curve.
+    pub fn respond(&self, h: &Point) -> (Vec<u8>, [u8; 20]) {
+        let k = self.parameters.curve.scalar_mul(h, &self.x);
+        (MESSAGE.to_vec(), sha1_hmac(&point_to_key(&k), MESSAGE))
+    }
+
+    pub fn is_private_key(&self, x: &Integer) -> bool {
+        &self.x == x
+    }
+}
+
+fn point_of_order(curve: &WeierstrassCurve, order: &Integer, r: &Integer) -> Point {
+    let cofactor = Integer::from(order / r);
+    loop {
+        let h = curve.scalar_mul(&curve.random_point(), &cofactor);
+        if h != Point::Infinity {
+            return h;
+        }
+    }
+}
+
+fn brute_force_mac(
+    curve: &WeierstrassCurve,
+    h: &Point,
+    r: &Integer,
+    message: &[u8],
+    mac: &[u8; 20],
+) -> Integer {
+    let mut k = Point::Infinity;
+    let mut x = Integer::new();
+    while &x < r {
+        if sha1_hmac(&point_to_key(&k), message) == *mac {
+            return x;
+        }
+        k = curve.add(&k, h);
+        x += 1;
+    }
+    panic!("no residue matches the MAC");
+}
+
+pub fn invalid_curve_attack(bob: &EcdhBob, parameters: &CurveParameters) -> Integer {
+    let mut candidates = Vec::new();
+    for (curve, order) in get_invalid_curves() {
+        for r in small_factors(&order, 1 << 16) {
+            candidates.push((curve.clone(), order.clone(), r));
+        }
+    }
+
+    let mut residues: Vec<(Integer, Integer)> = Vec::new();
+    let mut modulus = Integer::from(1);
+    for (curve, order, r) in candidates {
+        if modulus > parameters.n {
+            break;
+        }
+        if residues.iter().any(|(_, m)| *m == r) {
+            continue;
+        }
+        let h = point_of_order(&curve, &order, &r);
+        let (message, mac) = bob.respond(&h);
+        let residue = brute_force_mac(&curve, &h, &r, &message, &mac);
+        println!("x = {residue} mod {r}");
+
+        modulus *= &r;
+        residues.push((residue, r));
+    }
+
+    if modulus <= parameters.n {
+        panic!("not enough small factors to recover the key");
+    }
+    crt(&residues).0
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::s08::elliptic_curve::{generate_ecdh_key, generate_ecdh_session_key};
+
+    use super::*;
+
+    #[test]
+    fn weak_curve() {
+        let parameters = get_weak_curve_parameters();
+        assert!(parameters.is_valid_public_key(&parameters.g));
+
+        let (a_public, a_private) = generate_ecdh_key(&parameters);
+        let (b_public, b_private) = generate_ecdh_key(&parameters);
+        assert_eq!(
+            generate_ecdh_session_key(&b_public, &a_private, &parameters.curve),
+            generate_ecdh_session_key(&a_public, &b_private, &parameters.curve)
+        );
+
+        for (curve, order) in get_invalid_curves() {
+            let point = curve.random_point();
+            assert!(!parameters.curve.is_on_curve(&point));
+            assert!(curve.order_divides(&point, &order));
+        }
+    }
+
+    #[test]
+    fn s08e03() {
+        let parameters = get_weak_curve_parameters();
+        let bob = EcdhBob::new(&parameters);
+
+        let x = invalid_curve_attack(&bob, &parameters);
+        assert!(bob.is_private_key(&x));
+        assert_eq!(
+            parameters.curve.scalar_mul(&parameters.g, &x),
+            bob.public_key()
+        );
+    }
+}
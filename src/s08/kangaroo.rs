@@ -0,0 +1,218 @@
+use rug::{ops::DivRounding, Integer};
+
+use crate::util::group::Group;
+
+// Failed walks are retried with a different jump function before giving up.
+const ATTEMPTS: u64 = 4;
+
+struct Jumps<G: Group> {
+    salt: u64,
+    distances: Vec<u64>,
+    elements: Vec<G::Element>,
+}
+
+impl<G: Group> Jumps<G> {
+    // Jump distances are powers of two with a mean of roughly sqrt(width) / 2.
+    fn new(group: &G, g: &G::Element, width: u64, salt: u64) -> Jumps<G> {
+        let target = (width as f64).sqrt() / 2.0;
+        let k = (1..64)
+            .find(|k| ((1u64 << k) - 1) as f64 / *k as f64 >= target)
+            .unwrap();
+
+        let distances: Vec<u64> = (0..k).map(|i| 1 << i).collect();
+        let elements = distances
+            .iter()
+            .map(|d| group.pow(g, &Integer::from(*d)))
+            .collect();
+        Jumps {
+            salt,
+            distances,
+            elements,
+        }
+    }
+
+    fn mean(&self) -> u64 {
+        self.distances.iter().sum::<u64>() / self.distances.len() as u64
+    }
+
+    fn index(&self, group: &G, y: &G::Element) -> usize {
+        (group.fingerprint(y).wrapping_add(self.salt) % self.distances.len() as u64) as usize
+    }
+}
+
+fn walk<G: Group>(
+    group: &G,
+    g: &G::Element,
+    y: &G::Element,
+    a: &Integer,
+    width: u64,
+    jumps: &Jumps<G>,
+) -> Option<Integer> {
+    // the tame kangaroo starts at the upper bound and sets a trap
+    let mut tame = group.pow(g, &Integer::from(a + width));
+    let mut tame_distance = 0u64;
+    for _ in 0..4 * jumps.mean() {
+        let i = jumps.index(group, &tame);
+        tame_distance += jumps.distances[i];
+        tame = group.operate(&tame, &jumps.elements[i]);
+    }
+
+    // the wild kangaroo starts at y and either lands in the trap or overtakes it
+    let mut wild = y.clone();
+    let mut wild_distance = 0u64;
+    while wild_distance <= width + tame_distance {
+        if wild == tame {
+            return Some(Integer::from(a + width) + tame_distance - wild_distance);
+        }
+        let i = jumps.index(group, &wild);
+        wild_distance += jumps.distances[i];
+        wild = group.operate(&wild, &jumps.elements[i]);
+    }
+    None
+}
+
+// Finds x in [a, b] with g^x = y in about 2 * sqrt(b - a) group operations.
+pub fn kangaroo<G: Group>(
+    group: &G,
+    g: &G::Element,
+    y: &G::Element,
+    a: &Integer,
+    b: &Integer,
+) -> Option<Integer> {
+    if b < a {
+        return None;
+    }
+    let width = Integer::from(b - a)
+        .to_u64()
+        .expect("interval is too large for the kangaroo attack");
+
+    (0..ATTEMPTS).find_map(|salt| {
+        let jumps = Jumps::new(group, g, width, salt);
+        walk(group, g, y, a, width, &jumps)
+    })
+}
+
+// Finds x in [a, b] with g^x = y when x = n mod r is already known.
+// Writing x = n + r * m reduces this to (g^r)^m = y * g^-n for a range of
+// m that is r times smaller.
+pub fn kangaroo_with_residue<G: Group>(
+    group: &G,
+    g: &G::Element,
+    y: &G::Element,
+    n: &Integer,
+    r: &Integer,
+    a: &Integer,
+    b: &Integer,
+) -> Option<Integer> {
+    let g_r = group.pow(g, r);
+    let y_reduced = group.operate(y, &group.inverse(&group.pow(g, n)));
+
+    let lower = Integer::from(a - n).div_ceil(r.clone());
+    let upper = Integer::from(b - n).div_floor(r.clone());
+
+    let m = kangaroo(group, &g_r, &y_reduced, &lower, &upper)?;
+    Some(m * r + n)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        s05::dh::{get_nist_g, get_nist_p},
+        s06::dsa::{DsaParameters, DsaPrivate, DsaPublic},
+        s08::dh_small_subgroup::{recover_residues, MacBob},
+        util::{generators::generate_random_range, group::MultiplicativeGroup},
+    };
+
+    use super::*;
+
+    #[test]
+    fn kangaroo_dh() {
+        let p = get_nist_p();
+        let g = get_nist_g();
+        let group = MultiplicativeGroup::new(&p);
+
+        let a = Integer::from(1) << 100;
+        let b = Integer::from(&a + (1 << 20));
+        let x = generate_random_range(&Integer::new(), &Integer::from(1 << 20)) + &a;
+        let y = group.pow(&g, &x);
+
+        assert_eq!(kangaroo(&group, &g, &y, &a, &b), Some(x));
+        assert_eq!(
+            kangaroo(&group, &g, &y, &Integer::new(), &(Integer::from(1) << 30)),
+            None
+        );
+    }
+
+    #[test]
+    fn kangaroo_dsa() {
+        let parameters = DsaParameters::default_parameters();
+        let group = MultiplicativeGroup::new(&parameters.p);
+
+        let a = Integer::new();
+        let b = Integer::from(1) << 40;
+        let x = generate_random_range(&a, &b);
+        let public = DsaPublic {
+            y: group.pow(&parameters.g, &x),
+            parameters: parameters.clone(),
+        };
+        let private = DsaPrivate {
+            x: x.clone(),
+            parameters: parameters.clone(),
+        };
+
+        let recovered = kangaroo(&group, &parameters.g, &public.y, &a, &b).unwrap();
+        assert_eq!(recovered, private.x);
+
+        let signature = private.sign(b"kangaroo");
+        assert!(public.verify(b"kangaroo", &signature));
+    }
+
+    #[test]
+    fn kangaroo_residue() {
+        let parameters = DsaParameters::default_parameters();
+        let group = MultiplicativeGroup::new(&parameters.p);
+
+        // a 60 bit key of which the low 24 bits have leaked
+        let x = generate_random_range(&Integer::new(), &(Integer::from(1) << 60));
+        let y = group.pow(&parameters.g, &x);
+        let r = Integer::from(1 << 24);
+        let n = Integer::from(&x % &r);
+
+        let recovered = kangaroo_with_residue(
+            &group,
+            &parameters.g,
+            &y,
+            &n,
+            &r,
+            &Integer::new(),
+            &(Integer::from(1) << 60),
+        );
+        assert_eq!(recovered, Some(x));
+    }
+
+    #[test]
+    fn s08e02() {
+        let p = Integer::from_str_radix("11470374874925275658116663507232161402086650258453896274534991676898999262641581519101074740642369848233294239851519212341844337347119899874391456329785623", 10).unwrap();
+        let q = Integer::from_str_radix("335062023296420808191071248367701059461", 10).unwrap();
+        let g = Integer::from_str_radix("622952335333961296978159266084741085889881358738459939978290179936063635566740258555167783009058567397963466103140082647486611657350811560630587013183357", 10).unwrap();
+        let group = MultiplicativeGroup::new(&p);
+
+        let y = group.pow(&g, &Integer::from(705485));
+        let x = kangaroo(&group, &g, &y, &Integer::new(), &Integer::from(1 << 20));
+        assert_eq!(x, Some(Integer::from(705485)));
+
+        let y = Integer::from_str_radix("9388897478013399550694114614498790691034187453089355259602614074132918843899833277397448144245883225611726912025846772975325932794909655215329941809013733", 10).unwrap();
+        let x = kangaroo(&group, &g, &y, &Integer::new(), &Integer::from(1u64 << 40));
+        assert_eq!(group.pow(&g, &x.unwrap()), y);
+
+        // the small factors of (p - 1) / q leave about 40 bits of the key for the kangaroo
+        let bob = MacBob::new(&p, &g, &q);
+        let (n, r) = recover_residues(&bob, &p, &q);
+        assert!(r < q);
+
+        let y = bob.public_key();
+        let x = kangaroo_with_residue(&group, &g, &y, &n, &r, &Integer::new(), &q).unwrap();
+        assert!(bob.is_private_key(&x));
+    }
+}
@@ -0,0 +1,12 @@
+pub mod aes_gcm;
+pub mod biased_nonce;
+pub mod dh_small_subgroup;
+pub mod duplicate_signature;
+pub mod ecdsa;
+pub mod elliptic_curve;
+pub mod gcm_nonce_reuse;
+pub mod gcm_truncated_mac;
+pub mod invalid_curve;
+pub mod kangaroo;
+pub mod montgomery_curve;
+pub mod twist_attack;
@@ -0,0 +1,221 @@
+use rug::Integer;
+
+use crate::util::algebra::sqrt_mod;
+
+use super::elliptic_curve::{Point, WeierstrassCurve};
+
+// Bv^2 = u^3 + Au^2 + u over GF(p)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MontgomeryCurve {
+    pub p: Integer,
+    pub a: Integer,
+    pub b: Integer,
+}
+
+impl MontgomeryCurve {
+    pub fn new(p: &Integer, a: &Integer, b: &Integer) -> MontgomeryCurve {
+        MontgomeryCurve {
+            p: p.clone(),
+            a: a.clone(),
+            b: b.clone(),
+        }
+    }
+
+    fn reduce(&self, value: Integer) -> Integer {
+        value.div_rem_euc(self.p.clone()).1
+    }
+
+    fn invert(&self, value: Integer) -> Integer {
+        self.reduce(value).invert(&self.p).unwrap()
+    }
+
+    // (u^3 + Au^2 + u) / B, which has to be a square for u to be on the curve
+    fn v_squared(&self, u: &Integer) -> Integer {
+        let rhs = Integer::from(u * u) * u + Integer::from(u * u) * &self.a + u;
+        self.reduce(rhs * self.invert(self.b.clone()))
+    }
+
+    pub fn is_on_curve(&self, u: &Integer) -> bool {
+        self.v_squared(u).legendre(&self.p) != -1
+    }
+
+    pub fn lift(&self, u: &Integer) -> Option<Point> {
+        let v = sqrt_mod(&self.v_squared(u), &self.p)?;
+        Some(Point::new(&self.reduce(u.clone()), &v))
+    }
+
+    // The curve Dv^2 = u^3 + Au^2 + u for a non-square D, which contains all
+    // u-coordinates that are not on this curve.
+    pub fn twist(&self) -> MontgomeryCurve {
+        let mut d = Integer::from(2);
+        while d.legendre(&self.p) != -1 {
+            d += 1;
+        }
+        MontgomeryCurve::new(&self.p, &self.a, &self.reduce(d * &self.b))
+    }
+
+    // The u-coordinate of k * P for P = (u, v), without ever computing v.
+    // Never checks that u is on the curve, so it works on the twist as well.
+    pub fn ladder(&self, u: &Integer, k: &Integer) -> Integer {
+        let mut u2 = Integer::from(1);
+        let mut w2 = Integer::new();
+        let mut u3 = u.clone();
+        let mut w3 = Integer::from(1);
+        for i in (0..self.p.significant_bits()).rev() {
+            let bit = k.get_bit(i);
+            if bit {
+                std::mem::swap(&mut u2, &mut u3);
+                std::mem::swap(&mut w2, &mut w3);
+            }
+            let u3_next = self
+                .reduce(Integer::from(&u2 * &u3) - Integer::from(&w2 * &w3))
+                .square();
+            let w3_next = self
+                .reduce(Integer::from(&u2 * &w3) - Integer::from(&w2 * &u3))
+                .square()
+                * u;
+            let u2_next = self
+                .reduce(Integer::from(&u2 * &u2) - Integer::from(&w2 * &w2))
+                .square();
+            let w2_next = Integer::from(&u2 * &w2)
+                * 4
+                * (Integer::from(&u2 * &u2) + Integer::from(&u2 * &w2) * &self.a + &w2 * &w2);
+            u3 = self.reduce(u3_next);
+            w3 = self.reduce(w3_next);
+            u2 = self.reduce(u2_next);
+            w2 = self.reduce(w2_next);
+            if bit {
+                std::mem::swap(&mut u2, &mut u3);
+                std::mem::swap(&mut w2, &mut w3);
+            }
+        }
+        // the point at infinity comes out as u = 0
+        self.reduce(u2 * w2.pow_mod(&Integer::from(&self.p - 2), &self.p).unwrap())
+    }
+
+    // y^2 = x^3 + ax + b with a = (3 - A^2) / 3B^2 and b = (2A^3 - 9A) / 27B^3
+    pub fn to_weierstrass(&self) -> WeierstrassCurve {
+        let a_squared = Integer::from(&self.a * &self.a);
+        let b_squared = Integer::from(&self.b * &self.b);
+        let a = Integer::from(3 - &a_squared) * self.invert(b_squared.clone() * 3);
+        let b = (a_squared * 2 - 9) * &self.a * self.invert(b_squared * &self.b * 27);
+        WeierstrassCurve::new(&self.p, &self.reduce(a), &self.reduce(b))
+    }
+
+    // x = u / B + A / 3B
+    pub fn u_to_weierstrass_x(&self, u: &Integer) -> Integer {
+        self.reduce(Integer::from(u * 3 + &self.a) * self.invert(Integer::from(&self.b * 3)))
+    }
+
+    // u = Bx - A / 3
+    pub fn u_from_weierstrass_x(&self, x: &Integer) -> Integer {
+        self.reduce(
+            Integer::from(&self.b * x) - Integer::from(&self.a) * self.invert(Integer::from(3)),
+        )
+    }
+
+    pub fn to_weierstrass_point(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(u, v) => Point::Affine(
+                self.u_to_weierstrass_x(u),
+                self.reduce(self.invert(self.b.clone()) * v),
+            ),
+        }
+    }
+
+    pub fn from_weierstrass_point(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => Point::Affine(
+                self.u_from_weierstrass_x(x),
+                self.reduce(Integer::from(&self.b * y)),
+            ),
+        }
+    }
+}
+
+// A curve together with the u-coordinate of a base point of prime order n
+// and the cofactor h.
+#[derive(Clone)]
+pub struct MontgomeryParameters {
+    pub curve: MontgomeryCurve,
+    pub u: Integer,
+    pub n: Integer,
+    pub h: Integer,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::s08::invalid_curve::get_weak_curve_parameters;
+
+    use super::*;
+
+    fn from_le_hex(hex: &str) -> Integer {
+        Integer::from_digits(&hex::decode(hex).unwrap(), rug::integer::Order::Lsf)
+    }
+
+    #[test]
+    fn x25519() {
+        // RFC 7748, section 5.2
+        let p = (Integer::from(1) << 255) - 19;
+        let curve = MontgomeryCurve::new(&p, &Integer::from(486662), &Integer::from(1));
+
+        let mut scalar =
+            hex::decode("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4")
+                .unwrap();
+        scalar[0] &= 248;
+        scalar[31] &= 127;
+        scalar[31] |= 64;
+        let k = Integer::from_digits(&scalar, rug::integer::Order::Lsf);
+        let u = from_le_hex("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c");
+
+        let result = curve.ladder(&u, &k);
+        assert_eq!(
+            hex::encode(result.to_digits::<u8>(rug::integer::Order::Lsf)),
+            "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"
+        );
+    }
+
+    #[test]
+    fn montgomery_weierstrass_conversion() {
+        let parameters = get_weak_curve_parameters();
+        let curve =
+            MontgomeryCurve::new(&parameters.curve.p, &Integer::from(534), &Integer::from(1));
+        let weierstrass = curve.to_weierstrass();
+        assert_eq!(
+            Integer::from(&weierstrass.a - &parameters.curve.a) % &weierstrass.p,
+            0
+        );
+        assert_eq!(weierstrass.b, parameters.curve.b);
+
+        let base = curve.lift(&Integer::from(4)).unwrap();
+        let g = curve.to_weierstrass_point(&base);
+        assert!(g == parameters.g || g == weierstrass.negate(&parameters.g));
+        assert_eq!(curve.from_weierstrass_point(&g), base);
+
+        assert_eq!(curve.ladder(&Integer::from(4), &parameters.n), 0);
+        for k in [1, 2, 3, 1000, 123456789] {
+            let k = Integer::from(k);
+            let expected = weierstrass.scalar_mul(&g, &k);
+            assert_eq!(
+                curve.ladder(&Integer::from(4), &k),
+                curve.u_from_weierstrass_x(expected.x().unwrap())
+            );
+        }
+
+        // a u-coordinate off the curve is on the twist and vice versa
+        let twist = curve.twist();
+        let mut u = Integer::from(5);
+        while curve.is_on_curve(&u) {
+            u += 1;
+        }
+        assert!(curve.lift(&u).is_none());
+        assert!(twist.is_on_curve(&u));
+        let point = twist.lift(&u).unwrap();
+        assert!(twist
+            .to_weierstrass()
+            .is_on_curve(&twist.to_weierstrass_point(&point)));
+    }
+}
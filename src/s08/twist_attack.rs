@@ -0,0 +1,231 @@
+use rug::Integer;
+
+use crate::{
+    s04::hmac::sha1_hmac,
+    util::{algebra::crt, generators::generate_random_range, integer::to_bytes},
+};
+
+use super::{
+    dh_small_subgroup::small_factors,
+    elliptic_curve::{Point, WeierstrassCurve},
+    kangaroo::kangaroo_with_residue,
+    montgomery_curve::{MontgomeryCurve, MontgomeryParameters},
+};
+
+const MESSAGE: &[u8] = b"crazy flamboyant for the rap enjoyment";
+
+// v^2 = u^3 + 534u^2 + u, the Montgomery form of the weak curve
+pub fn get_weak_montgomery_parameters() -> MontgomeryParameters {
+    MontgomeryParameters {
+        curve: MontgomeryCurve::new(
+            &Integer::from_str_radix("233970423115425145524320034830162017933", 10).unwrap(),
+            &Integer::from(534),
+            &Integer::from(1),
+        ),
+        u: Integer::from(4),
+        n: Integer::from_str_radix("29246302889428143187362802287225875743", 10).unwrap(),
+        h: Integer::from(8),
+    }
+}
+
+pub struct LadderBob {
+    parameters: MontgomeryParameters,
+    x: Integer,
+}
+
+impl LadderBob {
+    pub fn new(parameters: &MontgomeryParameters) -> LadderBob {
+        LadderBob {
+            parameters: parameters.clone(),
+            x: generate_random_range(&Integer::from(1), &parameters.n),
+        }
+    }
+
+    pub fn public_key(&self) -> Integer {
+        self.parameters.curve.ladder(&self.parameters.u, &self.x)
+    }
+
+    // Bob does not check that u is on his curve.
+    pub fn respond(&self, u: &Integer) -> (Vec<u8>, [u8; 20]) {
+        let k = self.parameters.curve.ladder(u, &self.x);
+        (MESSAGE.to_vec(), sha1_hmac(&to_bytes(&k), MESSAGE))
+    }
+
+    pub fn is_private_key(&self, x: &Integer) -> bool {
+        &self.x == x
+    }
+}
+
+// Points on the twist are handled in its Weierstrass form, which supports
+// additions, and sent to Bob as u-coordinates.
+struct Twist {
+    montgomery: MontgomeryCurve,
+    weierstrass: WeierstrassCurve,
+    order: Integer,
+}
+
+impl Twist {
+    fn new(parameters: &MontgomeryParameters) -> Twist {
+        let montgomery = parameters.curve.twist();
+        let weierstrass = montgomery.to_weierstrass();
+        // both curves together have 2p + 2 points
+        let order = Integer::from(&parameters.curve.p * 2) + 2 - &parameters.n * &parameters.h;
+        Twist {
+            montgomery,
+            weierstrass,
+            order,
+        }
+    }
+
+    fn u(&self, point: &Point) -> Integer {
+        match point {
+            Point::Infinity => Integer::new(),
+            Point::Affine(x, _) => self.montgomery.u_from_weierstrass_x(x),
+        }
+    }
+
+    fn point_of_order(&self, r: &Integer) -> Point {
+        let cofactor = Integer::from(&self.order / r);
+        loop {
+            let u = generate_random_range(&Integer::new(), &self.montgomery.p);
+            if let Some(point) = self.montgomery.lift(&u) {
+                let point = self.montgomery.to_weierstrass_point(&point);
+                let h = self.weierstrass.scalar_mul(&point, &cofactor);
+                if h != Point::Infinity {
+                    return h;
+                }
+            }
+        }
+    }
+
+    // The u-coordinate of kP equals that of -kP, so only k up to r / 2 is
+    // tried and the result is only known up to sign.
+    fn brute_force_mac(&self, h: &Point, r: &Integer, message: &[u8], mac: &[u8; 20]) -> Integer {
+        let mut k = Point::Infinity;
+        let mut x = Integer::new();
+        while x <= Integer::from(r / 2) {
+            if sha1_hmac(&to_bytes(&self.u(&k)), message) == *mac {
+                return x;
+            }
+            k = self.weierstrass.add(&k, h);
+            x += 1;
+        }
+        panic!("no residue matches the MAC");
+    }
+}
+
+// Returns c and modulus with x = ±c mod modulus, the product of the factors
+// of the twist order below bound. The signs of all residues are kept
+// consistent by querying Bob with the sum of all points so far.
+fn twist_residues(
+    bob: &LadderBob,
+    parameters: &MontgomeryParameters,
+    bound: u32,
+) -> (Integer, Integer) {
+    let twist = Twist::new(parameters);
+
+    let mut c = Integer::new();
+    let mut modulus = Integer::from(1);
+    let mut combined = Point::Infinity;
+    for r in small_factors(&twist.order, bound) {
+        let h = twist.point_of_order(&r);
+        let (message, mac) = bob.respond(&twist.u(&h));
+        let residue = twist.brute_force_mac(&h, &r, &message, &mac);
+        println!("x = ±{residue} mod {r}");
+
+        let next = twist.weierstrass.add(&combined, &h);
+        let (message, mac) = bob.respond(&twist.u(&next));
+        let negated = Integer::from(&r - &residue);
+        c = [residue, negated]
+            .into_iter()
+            .map(|d| crt(&[(c.clone(), modulus.clone()), (d, r.clone())]).0)
+            .find(|candidate| {
+                let k = twist.weierstrass.scalar_mul(&next, candidate);
+                sha1_hmac(&to_bytes(&twist.u(&k)), &message) == mac
+            })
+            .expect("no sign matches the MAC");
+        modulus *= &r;
+        combined = next;
+    }
+    (c, modulus)
+}
+
+// Bob's key can only ever be recovered up to sign, as the ladder gives the
+// same u-coordinate for x and -x.
+pub fn twist_attack(bob: &LadderBob, parameters: &MontgomeryParameters) -> Integer {
+    let (c, modulus) = twist_residues(bob, parameters, 1 << 22);
+
+    // Bob's public key lifts to ±xG, and x = ±c mod modulus
+    let curve = &parameters.curve;
+    let weierstrass = curve.to_weierstrass();
+    let g = curve.to_weierstrass_point(&curve.lift(&parameters.u).unwrap());
+    let y = curve.to_weierstrass_point(&curve.lift(&bob.public_key()).unwrap());
+    let lower = Integer::from(-&parameters.n);
+    for target in [y.clone(), weierstrass.negate(&y)] {
+        if let Some(x) = kangaroo_with_residue(
+            &weierstrass,
+            &g,
+            &target,
+            &c,
+            &modulus,
+            &lower,
+            &parameters.n,
+        ) {
+            return x.div_rem_euc(parameters.n.clone()).1;
+        }
+    }
+    panic!("kangaroo did not find the key");
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn twist_order() {
+        let parameters = get_weak_montgomery_parameters();
+        let twist = Twist::new(&parameters);
+
+        let mut u = Integer::from(2);
+        while parameters.curve.is_on_curve(&u) {
+            u += 1;
+        }
+        let point = twist
+            .montgomery
+            .to_weierstrass_point(&twist.montgomery.lift(&u).unwrap());
+        assert_eq!(twist.u(&point), u);
+        assert!(twist.weierstrass.order_divides(&point, &twist.order));
+
+        let h = twist.point_of_order(&Integer::from(107));
+        assert!(twist.weierstrass.order_divides(&h, &Integer::from(107)));
+        assert_eq!(
+            parameters.curve.ladder(&twist.u(&h), &Integer::from(107)),
+            0
+        );
+    }
+
+    #[test]
+    fn twist_residues_small_factors() {
+        let parameters = get_weak_montgomery_parameters();
+        let bob = LadderBob::new(&parameters);
+
+        // the factors 11, 107, 197 and 1621 are quick to brute force
+        let (c, modulus) = twist_residues(&bob, &parameters, 2000);
+        assert_eq!(modulus, 11 * 107 * 197 * 1621);
+
+        let x = bob.x.clone().div_rem_euc(modulus.clone()).1;
+        assert!(x == c || x == (Integer::from(&modulus - &c) % &modulus));
+    }
+
+    #[test]
+    #[ignore]
+    fn s08e04() {
+        let parameters = get_weak_montgomery_parameters();
+        let bob = LadderBob::new(&parameters);
+
+        let x = twist_attack(&bob, &parameters);
+        assert!(bob.is_private_key(&x) || bob.is_private_key(&(&parameters.n - x.clone())));
+        assert_eq!(parameters.curve.ladder(&parameters.u, &x), bob.public_key());
+    }
+}
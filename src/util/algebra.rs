@@ -1,6 +1,280 @@
+use std::collections::HashMap;
+
+use rug::Integer;
+
+// Combines x = r_i mod m_i for pairwise coprime m_i into x mod prod m_i.
+pub fn crt(residues: &[(Integer, Integer)]) -> (Integer, Integer) {
+    let mut result = Integer::new();
+    let mut modulus = Integer::from(1);
+    for (residue, m) in residues {
+        let inverse = modulus
+            .clone()
+            .invert(m)
+            .expect("moduli must be pairwise coprime");
+        let (_, t) = (Integer::from(residue - &result) * inverse).div_rem_euc(m.clone());
+        result += t * &modulus;
+        modulus *= m;
+    }
+    (result, modulus)
+}
+
+// Finds x < order with g^x = h mod p in about sqrt(order) steps.
+fn baby_step_giant_step(g: &Integer, h: &Integer, order: &Integer, p: &Integer) -> Option<Integer> {
+    let m: Integer = Integer::from(order.sqrt_ref()) + 1;
+    let steps = m.to_u64().expect("order is too large");
+
+    let mut baby_steps = HashMap::new();
+    let mut power = Integer::from(1);
+    for j in 0..steps {
+        baby_steps.entry(power.clone()).or_insert(j);
+        power = power * g % p;
+    }
+
+    let giant_step = g.clone().pow_mod(&-m.clone(), p).ok()?;
+    let mut gamma = h.clone();
+    for i in 0..steps {
+        if let Some(j) = baby_steps.get(&gamma) {
+            return Some(Integer::from(i) * &m + j);
+        }
+        gamma = gamma * &giant_step % p;
+    }
+    None
+}
+
+// Solves g^x = h mod p for a generator g, given the distinct prime factors of
+// a square-free p - 1.
+pub fn pohlig_hellman(
+    g: &Integer,
+    h: &Integer,
+    p: &Integer,
+    factors: &[Integer],
+) -> Option<Integer> {
+    let order = Integer::from(p - 1);
+    let mut residues = Vec::new();
+    for r in factors {
+        let cofactor = Integer::from(&order / r);
+        let g_r = g.clone().pow_mod(&cofactor, p).unwrap();
+        let h_r = h.clone().pow_mod(&cofactor, p).unwrap();
+        let x = baby_step_giant_step(&g_r, &h_r, r, p)?;
+        residues.push((x, r.clone()));
+    }
+    Some(crt(&residues).0)
+}
+
+// Tonelli-Shanks square root of n modulo an odd prime p.
+pub fn sqrt_mod(n: &Integer, p: &Integer) -> Option<Integer> {
+    let (_, n) = n.clone().div_rem_euc(p.clone());
+    if n == 0 {
+        return Some(n);
+    }
+    if n.legendre(p) != 1 {
+        return None;
+    }
+
+    let mut q = Integer::from(p - 1);
+    let s = q.find_one(0).unwrap();
+    q >>= s;
+    let mut z = Integer::from(2);
+    while z.legendre(p) != -1 {
+        z += 1;
+    }
+
+    let mut m = s;
+    let mut c = z.pow_mod(&q, p).unwrap();
+    let mut t = n.clone().pow_mod(&q, p).unwrap();
+    let mut r = n.pow_mod(&(Integer::from(&q + 1) / 2), p).unwrap();
+    while t != 1 {
+        let mut i = 0;
+        let mut t_power = t.clone();
+        while t_power != 1 {
+            t_power = t_power.square() % p;
+            i += 1;
+        }
+        let b = c.pow_mod(&(Integer::from(1) << (m - i - 1)), p).unwrap();
+        m = i;
+        c = b.clone().square() % p;
+        t = t * &c % p;
+        r = r * b % p;
+    }
+    Some(r)
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Gf2Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<Vec<u64>>,
+}
+
+impl Gf2Matrix {
+    pub fn new(rows: usize, cols: usize) -> Gf2Matrix {
+        Gf2Matrix {
+            rows,
+            cols,
+            data: vec![vec![0; cols.div_ceil(64)]; rows],
+        }
+    }
+
+    pub fn identity(size: usize) -> Gf2Matrix {
+        let mut matrix = Gf2Matrix::new(size, size);
+        for i in 0..size {
+            matrix.set(i, i, true);
+        }
+        matrix
+    }
+
+    pub fn from_columns(rows: usize, columns: &[Vec<bool>]) -> Gf2Matrix {
+        let mut matrix = Gf2Matrix::new(rows, columns.len());
+        for (col, column) in columns.iter().enumerate() {
+            for (row, bit) in column.iter().enumerate() {
+                matrix.set(row, col, *bit);
+            }
+        }
+        matrix
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        (self.data[row][col / 64] >> (col % 64)) & 1 == 1
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        if value {
+            self.data[row][col / 64] |= 1 << (col % 64);
+        } else {
+            self.data[row][col / 64] &= !(1 << (col % 64));
+        }
+    }
+
+    pub fn row(&self, row: usize) -> Vec<bool> {
+        (0..self.cols).map(|col| self.get(row, col)).collect()
+    }
+
+    pub fn column(&self, col: usize) -> Vec<bool> {
+        (0..self.rows).map(|row| self.get(row, col)).collect()
+    }
+
+    pub fn is_zero_row(&self, row: usize) -> bool {
+        self.data[row].iter().all(|word| *word == 0)
+    }
+
+    pub fn push_row(&mut self, row: &[bool]) {
+        if row.len() != self.cols {
+            panic!("row has the wrong length!");
+        }
+        self.data.push(vec![0; self.cols.div_ceil(64)]);
+        self.rows += 1;
+        for (col, bit) in row.iter().enumerate() {
+            self.set(self.rows - 1, col, *bit);
+        }
+    }
+
+    pub fn mul_vector(&self, vector: &[bool]) -> Vec<bool> {
+        if vector.len() != self.cols {
+            panic!("vector has the wrong length!");
+        }
+        (0..self.rows)
+            .map(|row| {
+                vector
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, bit)| **bit)
+                    .fold(false, |acc, (col, _)| acc ^ self.get(row, col))
+            })
+            .collect()
+    }
+
+    pub fn mul(&self, other: &Gf2Matrix) -> Gf2Matrix {
+        if self.cols != other.rows {
+            panic!("matrix dimensions do not match!");
+        }
+        let mut result = Gf2Matrix::new(self.rows, other.cols);
+        for row in 0..self.rows {
+            for k in 0..self.cols {
+                if self.get(row, k) {
+                    for (word, other_word) in result.data[row].iter_mut().zip(&other.data[k]) {
+                        *word ^= *other_word;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    pub fn transpose(&self) -> Gf2Matrix {
+        let mut result = Gf2Matrix::new(self.cols, self.rows);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                result.set(col, row, self.get(row, col));
+            }
+        }
+        result
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().reduce().len()
+    }
+
+    pub fn kernel(&self) -> Vec<Vec<bool>> {
+        let mut reduced = self.clone();
+        let pivots = reduced.reduce();
+
+        let mut is_pivot = vec![false; self.cols];
+        for pivot in &pivots {
+            is_pivot[*pivot] = true;
+        }
+
+        let mut basis = Vec::new();
+        for free in (0..self.cols).filter(|col| !is_pivot[*col]) {
+            let mut vector = vec![false; self.cols];
+            vector[free] = true;
+            for (row, pivot) in pivots.iter().enumerate() {
+                vector[*pivot] = reduced.get(row, free);
+            }
+            basis.push(vector);
+        }
+        basis
+    }
+
+    // Brings the matrix into reduced row echelon form and returns the pivot columns.
+    fn reduce(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        let mut pivot_row = 0;
+        for col in 0..self.cols {
+            if pivot_row == self.rows {
+                break;
+            }
+            let Some(found) = (pivot_row..self.rows).find(|row| self.get(*row, col)) else {
+                continue;
+            };
+            self.data.swap(pivot_row, found);
+            let pivot_data = self.data[pivot_row].clone();
+            for row in 0..self.rows {
+                if row != pivot_row && self.get(row, col) {
+                    for (word, pivot_word) in self.data[row].iter_mut().zip(&pivot_data) {
+                        *word ^= *pivot_word;
+                    }
+                }
+            }
+            pivots.push(col);
+            pivot_row += 1;
+        }
+        pivots
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
+    use super::*;
+
     #[test]
     fn extended_eucledan_test() {
         let a = rug::Integer::from(78);
@@ -21,4 +295,87 @@ mod tests {
         let inv = e.invert(&m).unwrap();
         assert_eq!(inv, rug::Integer::from(2753));
     }
+
+    #[test]
+    fn crt_test() {
+        let residues = [
+            (Integer::from(2), Integer::from(3)),
+            (Integer::from(3), Integer::from(5)),
+            (Integer::from(2), Integer::from(7)),
+        ];
+        assert_eq!(crt(&residues), (Integer::from(23), Integer::from(105)));
+
+        let x = Integer::from(123456789);
+        let moduli = [1009, 1013, 1019];
+        let residues: Vec<(Integer, Integer)> = moduli
+            .iter()
+            .map(|m| (Integer::from(&x % *m), Integer::from(*m)))
+            .collect();
+        assert_eq!(crt(&residues).0, x);
+    }
+
+    #[test]
+    fn pohlig_hellman_test() {
+        // p - 1 = 2 * 3 * 5 * 7 * 11 * 13 * 17 * 19 * 29 * 31 * 37 * 41 * 43 * 47 * 53 * 59
+        let p = Integer::from(83598276093661419091u128);
+        let factors: Vec<Integer> = [2, 3, 5, 7, 11, 13, 17, 19, 29, 31, 37, 41, 43, 47, 53, 59]
+            .iter()
+            .map(|r| Integer::from(*r))
+            .collect();
+        let g = (2..)
+            .map(Integer::from)
+            .find(|g| {
+                factors
+                    .iter()
+                    .all(|r| g.clone().pow_mod(&(Integer::from(&p - 1) / r), &p).unwrap() != 1)
+            })
+            .unwrap();
+
+        let x = Integer::from(12345678901234567890u64);
+        let h = g.clone().pow_mod(&x, &p).unwrap();
+        assert_eq!(pohlig_hellman(&g, &h, &p, &factors), Some(x));
+    }
+
+    #[test]
+    fn sqrt_mod_test() {
+        // p = 5 mod 8 and p = 1 mod 16 need the full Tonelli-Shanks loop
+        for p in [
+            13u128,
+            17,
+            97,
+            7681,
+            233970423115425145524320034830162017933,
+        ] {
+            let p = Integer::from(p);
+            for n in 0..50 {
+                let n = Integer::from(n);
+                match sqrt_mod(&n, &p) {
+                    Some(r) => assert_eq!(r.square() % &p, Integer::from(&n % &p)),
+                    None => assert_eq!(n.legendre(&p), -1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gf2_kernel_test() {
+        let mut matrix = Gf2Matrix::new(0, 130);
+        for _ in 0..100 {
+            let row: Vec<bool> = (0..130).map(|_| rand::random()).collect();
+            matrix.push_row(&row);
+        }
+        let rank = matrix.rank();
+
+        let kernel = matrix.kernel();
+        assert_eq!(kernel.len(), 130 - rank);
+        for vector in &kernel {
+            assert!(matrix.mul_vector(vector).iter().all(|bit| !bit));
+        }
+
+        let basis = Gf2Matrix::from_columns(130, &kernel);
+        assert_eq!(basis.rank(), kernel.len());
+        assert_eq!(matrix.mul(&basis).rank(), 0);
+        assert_eq!(basis.transpose().transpose(), basis);
+        assert_eq!(Gf2Matrix::identity(130).mul(&basis), basis);
+    }
 }
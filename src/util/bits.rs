@@ -61,6 +61,18 @@ pub fn u8_vector_to_u32(vec: &[u8]) -> u32 {
     res
 }
 
+pub fn u8_vector_to_u64(vec: &[u8]) -> u64 {
+    if vec.len() != 8 {
+        panic!("Length should be 8")
+    }
+    let mut res = 0;
+    for value in vec.iter() {
+        res <<= 8;
+        res += *value as u64;
+    }
+    res
+}
+
 pub fn u8_vector_to_u32_reverse(vec: &[u8]) -> u32 {
     if vec.len() != 4 {
         panic!("Length should be 4")
@@ -0,0 +1,85 @@
+use aes::{
+    cipher::{BlockDecrypt, BlockEncrypt, BlockSizeUser, KeyInit},
+    Aes128, Block,
+};
+
+use super::error::{CryptoError, Result};
+
+pub trait BlockCipher {
+    fn block_size(&self) -> usize;
+    fn encrypt_block(&self, block: &mut [u8]);
+    fn decrypt_block(&self, block: &mut [u8]);
+}
+
+impl<T> BlockCipher for T
+where
+    T: BlockEncrypt + BlockDecrypt + BlockSizeUser<BlockSize = aes::cipher::consts::U16>,
+{
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        BlockEncrypt::encrypt_block(self, Block::from_mut_slice(block));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        BlockDecrypt::decrypt_block(self, Block::from_mut_slice(block));
+    }
+}
+
+pub fn aes128(key: &[u8]) -> Result<Aes128> {
+    Aes128::new_from_slice(key).map_err(|_| CryptoError::InvalidKeyLength(key.len()))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use aes::{Aes192, Aes256};
+
+    use super::*;
+
+    #[test]
+    fn aes_fips197_kat() {
+        let plain = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+
+        let aes128 =
+            Aes128::new_from_slice(&hex::decode("000102030405060708090a0b0c0d0e0f").unwrap())
+                .unwrap();
+        let mut block = plain.clone();
+        BlockCipher::encrypt_block(&aes128, &mut block);
+        assert_eq!(hex::encode(&block), "69c4e0d86a7b0430d8cdb78070b4c55a");
+        BlockCipher::decrypt_block(&aes128, &mut block);
+        assert_eq!(block, plain);
+
+        let aes192 = Aes192::new_from_slice(
+            &hex::decode("000102030405060708090a0b0c0d0e0f1011121314151617").unwrap(),
+        )
+        .unwrap();
+        let mut block = plain.clone();
+        BlockCipher::encrypt_block(&aes192, &mut block);
+        assert_eq!(hex::encode(&block), "dda97ca4864cdfe06eaf70a0ec0d7191");
+        BlockCipher::decrypt_block(&aes192, &mut block);
+        assert_eq!(block, plain);
+
+        let aes256 = Aes256::new_from_slice(
+            &hex::decode("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f")
+                .unwrap(),
+        )
+        .unwrap();
+        let mut block = plain.clone();
+        BlockCipher::encrypt_block(&aes256, &mut block);
+        assert_eq!(hex::encode(&block), "8ea2b7ca516745bfeafc49904b496089");
+        BlockCipher::decrypt_block(&aes256, &mut block);
+        assert_eq!(block, plain);
+    }
+
+    #[test]
+    fn aes128_key_length() {
+        assert!(aes128(&[0; 16]).is_ok());
+        assert_eq!(
+            aes128(&[0; 15]).err(),
+            Some(CryptoError::InvalidKeyLength(15))
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+// Raw DEFLATE (RFC 1951) with a single fixed Huffman block and greedy LZ77 matching.
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 256;
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_count: usize,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            bytes: Vec::new(),
+            bit_count: 0,
+        }
+    }
+
+    // extra bits and header fields are written least significant bit first
+    fn write_bits(&mut self, value: usize, len: u8) {
+        for i in 0..len {
+            if self.bit_count.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            let bit = ((value >> i) & 1) as u8;
+            *self.bytes.last_mut().unwrap() |= bit << (self.bit_count % 8);
+            self.bit_count += 1;
+        }
+    }
+
+    // Huffman codes are written most significant bit first
+    fn write_code(&mut self, code: usize, len: u8) {
+        for i in (0..len).rev() {
+            self.write_bits((code >> i) & 1, 1);
+        }
+    }
+
+    fn write_symbol(&mut self, symbol: usize) {
+        match symbol {
+            0..=143 => self.write_code(0x30 + symbol, 8),
+            144..=255 => self.write_code(0x190 + symbol - 144, 9),
+            256..=279 => self.write_code(symbol - 256, 7),
+            _ => self.write_code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn write_match(&mut self, length: usize, distance: usize) {
+        let length_code = LENGTH_BASE
+            .iter()
+            .rposition(|base| *base <= length)
+            .unwrap();
+        self.write_symbol(257 + length_code);
+        self.write_bits(length - LENGTH_BASE[length_code], LENGTH_EXTRA[length_code]);
+
+        let distance_code = DISTANCE_BASE
+            .iter()
+            .rposition(|base| *base <= distance)
+            .unwrap();
+        self.write_code(distance_code, 5);
+        self.write_bits(
+            distance - DISTANCE_BASE[distance_code],
+            DISTANCE_EXTRA[distance_code],
+        );
+    }
+}
+
+fn longest_match(
+    input: &[u8],
+    pos: usize,
+    chains: &HashMap<&[u8], Vec<usize>>,
+) -> Option<(usize, usize)> {
+    if pos + MIN_MATCH > input.len() {
+        return None;
+    }
+    let candidates = chains.get(&input[pos..pos + MIN_MATCH])?;
+    let max_len = (input.len() - pos).min(MAX_MATCH);
+
+    let mut best: Option<(usize, usize)> = None;
+    for start in candidates.iter().rev().take(MAX_CHAIN) {
+        if pos - start > WINDOW_SIZE {
+            break;
+        }
+        let len = (0..max_len)
+            .take_while(|i| input[start + i] == input[pos + i])
+            .count();
+        if len >= MIN_MATCH && best.is_none_or(|(best_len, _)| len > best_len) {
+            best = Some((len, pos - start));
+            if len == max_len {
+                break;
+            }
+        }
+    }
+    best
+}
+
+pub fn deflate(input: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    // BFINAL = 1, BTYPE = 01 (fixed Huffman codes)
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    let mut chains: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    let mut pos = 0;
+    while pos < input.len() {
+        let step = match longest_match(input, pos, &chains) {
+            Some((length, distance)) => {
+                writer.write_match(length, distance);
+                length
+            }
+            None => {
+                writer.write_symbol(input[pos] as usize);
+                1
+            }
+        };
+        for i in pos..pos + step {
+            if i + MIN_MATCH <= input.len() {
+                chains.entry(&input[i..i + MIN_MATCH]).or_default().push(i);
+            }
+        }
+        pos += step;
+    }
+
+    writer.write_symbol(256);
+    writer.bytes
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // Minimal inflater for fixed Huffman blocks, written from RFC 1951
+    // independently of the encoder above.
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        bit_count: usize,
+    }
+
+    impl BitReader<'_> {
+        fn read_bits(&mut self, len: u8) -> usize {
+            let mut value = 0;
+            for i in 0..len {
+                let bit = (self.bytes[self.bit_count / 8] >> (self.bit_count % 8)) & 1;
+                value |= (bit as usize) << i;
+                self.bit_count += 1;
+            }
+            value
+        }
+
+        fn read_code(&mut self, len: u8) -> usize {
+            (0..len).fold(0, |code, _| (code << 1) | self.read_bits(1))
+        }
+
+        fn read_symbol(&mut self) -> usize {
+            let code = self.read_code(7);
+            if code <= 0x17 {
+                return 256 + code;
+            }
+            let code = (code << 1) | self.read_bits(1);
+            match code {
+                0x30..=0xbf => code - 0x30,
+                0xc0..=0xc7 => 280 + code - 0xc0,
+                _ => 144 + ((code << 1) | self.read_bits(1)) - 0x190,
+            }
+        }
+    }
+
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        let mut reader = BitReader {
+            bytes: data,
+            bit_count: 0,
+        };
+        let mut output = Vec::new();
+        loop {
+            let last_block = reader.read_bits(1) == 1;
+            assert_eq!(reader.read_bits(2), 1, "only fixed Huffman blocks");
+            loop {
+                let symbol = reader.read_symbol();
+                match symbol {
+                    0..=255 => output.push(symbol as u8),
+                    256 => break,
+                    _ => {
+                        let code = symbol - 257;
+                        let length = LENGTH_BASE[code] + reader.read_bits(LENGTH_EXTRA[code]);
+                        let code = reader.read_code(5);
+                        let distance = DISTANCE_BASE[code] + reader.read_bits(DISTANCE_EXTRA[code]);
+                        for _ in 0..length {
+                            output.push(output[output.len() - distance]);
+                        }
+                    }
+                }
+            }
+            if last_block {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn inflate_reference_vectors() {
+        // zlib output with the fixed Huffman strategy
+        assert_eq!(
+            inflate(&hex::decode("4b4c4a4e84a1ff00").unwrap()),
+            b"abcabcabcabc\xff"
+        );
+        assert_eq!(
+            inflate(
+                &hex::decode("8b74f5f1f10f57080e75f2750cf2f4738d1ce58ff247f923860f00").unwrap()
+            ),
+            b"YELLOW SUBMARINE".repeat(64)
+        );
+    }
+
+    #[test]
+    fn deflate_test() {
+        assert_eq!(deflate(b""), [0x03, 0x00]);
+        assert_eq!(deflate(b"a"), [0x4b, 0x04, 0x00]);
+        assert_eq!(hex::encode(deflate(b"abcabcabcabc\xff")), "4b4c4a86a3ff00");
+
+        let repetitive = [b"YELLOW SUBMARINE".as_slice(); 64].concat();
+        assert!(deflate(&repetitive).len() < 32);
+
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        // the second copy of all_bytes lies near the end of the window
+        let filler: Vec<u8> = (0..30000u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+            .collect();
+        let far_match = [&all_bytes[..], &filler, &all_bytes, &[0; 300]].concat();
+        for input in [&b""[..], b"a", &all_bytes, &repetitive, &far_match] {
+            assert_eq!(inflate(&deflate(input)), input);
+        }
+    }
+}
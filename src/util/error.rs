@@ -0,0 +1,50 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, CryptoError>;
+
+// Errors of the cipher and padding functions. Lengths are the offending ones
+// as given by the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CryptoError {
+    InvalidKeyLength(usize),
+    InvalidIvLength(usize),
+    InvalidCiphertextLength(usize),
+    InvalidPadding,
+    TextTooLong(usize),
+    InvalidBlockSize(usize),
+    InvalidTagLength(usize),
+    InvalidTag,
+    InvalidUtf8,
+    InvalidMessageLength(usize),
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CryptoError::InvalidKeyLength(len) => write!(f, "invalid key length {len}"),
+            CryptoError::InvalidIvLength(len) => write!(f, "invalid IV length {len}"),
+            CryptoError::InvalidCiphertextLength(len) => {
+                write!(
+                    f,
+                    "ciphertext length {len} is not a multiple of the block size"
+                )
+            }
+            CryptoError::InvalidPadding => write!(f, "invalid pkcs7 padding"),
+            CryptoError::TextTooLong(len) => {
+                write!(f, "text length {len} is bigger than the padding length")
+            }
+            CryptoError::InvalidBlockSize(size) => write!(f, "unsupported block size {size}"),
+            CryptoError::InvalidTagLength(len) => write!(f, "invalid tag length {len}"),
+            CryptoError::InvalidTag => write!(f, "authentication tag does not match"),
+            CryptoError::InvalidUtf8 => write!(f, "plaintext is not valid UTF-8"),
+            CryptoError::InvalidMessageLength(len) => {
+                write!(
+                    f,
+                    "message length {len} is not a multiple of the block size"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
@@ -32,6 +32,37 @@ pub fn generate_prime(bits: usize) -> Integer {
     }
 }
 
+// A prime p of the given size where p - 1 = 2 * r_1 * ... * r_n for distinct
+// primes r_i of factor_bits bits, except for a last one of up to twice that.
+// Returns p together with the prime factors of p - 1.
+pub fn generate_smooth_prime(bits: usize, factor_bits: usize) -> (Integer, Vec<Integer>) {
+    loop {
+        let mut factors = vec![Integer::from(2)];
+        let mut product = Integer::from(2);
+        while product.significant_bits() as usize + 2 * factor_bits <= bits {
+            let r = generate_prime(factor_bits);
+            if !factors.contains(&r) {
+                product *= &r;
+                factors.push(r);
+            }
+        }
+
+        // the last factor brings p to exactly the requested size
+        let lower = (Integer::from(1) << (bits - 1)) / &product + 1;
+        let upper = ((Integer::from(1) << bits) - 2) / &product;
+        let r = generate_random_range(&lower, &upper).next_prime();
+        if r > upper || factors.contains(&r) {
+            continue;
+        }
+
+        let candidate: Integer = product * &r + 1;
+        if candidate.is_probably_prime(30) != rug::integer::IsPrime::No {
+            factors.push(r);
+            return (candidate, factors);
+        }
+    }
+}
+
 pub fn generate_random_range(lower: &Integer, upper: &Integer) -> Integer {
     let bits = upper.significant_bits();
     loop {
@@ -55,6 +86,20 @@ mod tests {
         assert!(random < 8);
     }
 
+    #[test]
+    fn test_smooth_prime() {
+        let (p, factors) = generate_smooth_prime(512, 16);
+        assert_eq!(p.significant_bits(), 512);
+        assert_ne!(p.is_probably_prime(30), rug::integer::IsPrime::No);
+
+        let product = factors.iter().fold(Integer::from(1), |acc, r| acc * r);
+        assert_eq!(product, Integer::from(&p - 1));
+        for r in &factors {
+            assert!(r.significant_bits() <= 32);
+            assert_ne!(r.is_probably_prime(30), rug::integer::IsPrime::No);
+        }
+    }
+
     #[test]
     fn test_random_range() {
         let lower = Integer::from(3);
@@ -0,0 +1,164 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Mul, MulAssign, Sub},
+};
+
+// x^128 = x^7 + x^2 + x + 1
+const REDUCTION: u128 = 0x87;
+
+// Bit i of the inner value is the coefficient of x^i. GCM serializes blocks
+// with the coefficient of x^0 in the most significant bit of the first byte.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Gf128(pub u128);
+
+impl Gf128 {
+    pub fn zero() -> Gf128 {
+        Gf128(0)
+    }
+
+    pub fn one() -> Gf128 {
+        Gf128(1)
+    }
+
+    pub fn random() -> Gf128 {
+        Gf128(rand::random())
+    }
+
+    // Callers have to pass at most 16 bytes, shorter blocks are zero padded.
+    pub(crate) fn from_block(block: &[u8]) -> Gf128 {
+        if block.len() > 16 {
+            panic!("block must not be longer than 16 Bytes!");
+        }
+        let mut bytes = [0; 16];
+        bytes[..block.len()].copy_from_slice(block);
+        Gf128(u128::from_be_bytes(bytes).reverse_bits())
+    }
+
+    pub fn to_block(self) -> [u8; 16] {
+        self.0.reverse_bits().to_be_bytes()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn square(self) -> Gf128 {
+        self * self
+    }
+
+    pub fn pow(self, exponent: u128) -> Gf128 {
+        let mut result = Gf128::one();
+        let mut base = self;
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base = base.square();
+            exponent >>= 1;
+        }
+        result
+    }
+
+    pub fn inverse(self) -> Option<Gf128> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(self.pow(u128::MAX - 1))
+    }
+}
+
+impl Add for Gf128 {
+    type Output = Gf128;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Gf128) -> Gf128 {
+        Gf128(self.0 ^ rhs.0)
+    }
+}
+
+impl AddAssign for Gf128 {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn add_assign(&mut self, rhs: Gf128) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl Sub for Gf128 {
+    type Output = Gf128;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Gf128) -> Gf128 {
+        Gf128(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf128 {
+    type Output = Gf128;
+
+    fn mul(self, rhs: Gf128) -> Gf128 {
+        let mut result = 0;
+        let mut a = self.0;
+        let mut b = rhs.0;
+        while b != 0 {
+            if b & 1 == 1 {
+                result ^= a;
+            }
+            let overflow = a >> 127 == 1;
+            a <<= 1;
+            if overflow {
+                a ^= REDUCTION;
+            }
+            b >>= 1;
+        }
+        Gf128(result)
+    }
+}
+
+impl MulAssign for Gf128 {
+    fn mul_assign(&mut self, rhs: Gf128) {
+        *self = *self * rhs;
+    }
+}
+
+impl fmt::Debug for Gf128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Gf128({})", hex::encode(self.to_block()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn gf128_block_roundtrip() {
+        let block = hex::decode("66e94bd4ef8a2c3b884cfa59ca342b2e").unwrap();
+        assert_eq!(Gf128::from_block(&block).to_block().to_vec(), block);
+
+        let mut x = [0; 16];
+        x[0] = 0x40;
+        assert_eq!(Gf128::from_block(&x), Gf128(2));
+    }
+
+    #[test]
+    fn gf128_arithmetic() {
+        let a = Gf128::random();
+        let b = Gf128::random();
+        let c = Gf128::random();
+
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!(a * b, b * a);
+        assert_eq!(a + a, Gf128::zero());
+        assert_eq!(a * Gf128::one(), a);
+
+        if !a.is_zero() {
+            assert_eq!(a * a.inverse().unwrap(), Gf128::one());
+        }
+        assert_eq!(Gf128::zero().inverse(), None);
+
+        // x^127 * x = x^7 + x^2 + x + 1
+        assert_eq!(Gf128(1 << 127) * Gf128(2), Gf128(0x87));
+    }
+}
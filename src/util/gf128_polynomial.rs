@@ -0,0 +1,394 @@
+use std::ops::{Add, Mul, Sub};
+
+use super::gf128::Gf128;
+
+// coefficients[i] is the coefficient of X^i, there are no leading zeros.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Gf128Polynomial {
+    coefficients: Vec<Gf128>,
+}
+
+impl Gf128Polynomial {
+    pub fn new(coefficients: &[Gf128]) -> Gf128Polynomial {
+        let mut polynomial = Gf128Polynomial {
+            coefficients: coefficients.to_vec(),
+        };
+        polynomial.normalize();
+        polynomial
+    }
+
+    pub fn zero() -> Gf128Polynomial {
+        Gf128Polynomial {
+            coefficients: Vec::new(),
+        }
+    }
+
+    pub fn one() -> Gf128Polynomial {
+        Gf128Polynomial::constant(Gf128::one())
+    }
+
+    pub fn x() -> Gf128Polynomial {
+        Gf128Polynomial::new(&[Gf128::zero(), Gf128::one()])
+    }
+
+    pub fn constant(c: Gf128) -> Gf128Polynomial {
+        Gf128Polynomial::new(&[c])
+    }
+
+    pub fn random(degree_below: usize) -> Gf128Polynomial {
+        let coefficients: Vec<Gf128> = (0..degree_below).map(|_| Gf128::random()).collect();
+        Gf128Polynomial::new(&coefficients)
+    }
+
+    pub fn coefficients(&self) -> &[Gf128] {
+        &self.coefficients
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.coefficients == [Gf128::one()]
+    }
+
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn leading_coefficient(&self) -> Gf128 {
+        *self.coefficients.last().unwrap_or(&Gf128::zero())
+    }
+
+    pub fn evaluate(&self, x: Gf128) -> Gf128 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Gf128::zero(), |acc, c| acc * x + *c)
+    }
+
+    pub fn monic(&self) -> Gf128Polynomial {
+        match self.leading_coefficient().inverse() {
+            Some(inverse) => self.scale(inverse),
+            None => Gf128Polynomial::zero(),
+        }
+    }
+
+    pub fn scale(&self, factor: Gf128) -> Gf128Polynomial {
+        let coefficients: Vec<Gf128> = self.coefficients.iter().map(|c| *c * factor).collect();
+        Gf128Polynomial::new(&coefficients)
+    }
+
+    pub fn derivative(&self) -> Gf128Polynomial {
+        // in characteristic 2 only the odd powers survive
+        let coefficients: Vec<Gf128> = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| if i % 2 == 1 { *c } else { Gf128::zero() })
+            .collect();
+        Gf128Polynomial::new(&coefficients)
+    }
+
+    pub fn div_rem(&self, divisor: &Gf128Polynomial) -> (Gf128Polynomial, Gf128Polynomial) {
+        let divisor_degree = divisor.degree().expect("division by zero polynomial");
+        let lead_inverse = divisor.leading_coefficient().inverse().unwrap();
+
+        let mut remainder = self.coefficients.clone();
+        if remainder.len() <= divisor_degree {
+            return (Gf128Polynomial::zero(), self.clone());
+        }
+        let mut quotient = vec![Gf128::zero(); remainder.len() - divisor_degree];
+
+        for i in (0..quotient.len()).rev() {
+            let factor = remainder[i + divisor_degree] * lead_inverse;
+            quotient[i] = factor;
+            for (j, c) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] += factor * *c;
+            }
+        }
+        remainder.truncate(divisor_degree);
+
+        (
+            Gf128Polynomial::new(&quotient),
+            Gf128Polynomial::new(&remainder),
+        )
+    }
+
+    pub fn rem(&self, divisor: &Gf128Polynomial) -> Gf128Polynomial {
+        self.div_rem(divisor).1
+    }
+
+    pub fn gcd(&self, other: &Gf128Polynomial) -> Gf128Polynomial {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let r = a.rem(&b);
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    pub fn pow_mod(&self, exponent: u128, modulus: &Gf128Polynomial) -> Gf128Polynomial {
+        let mut result = Gf128Polynomial::one().rem(modulus);
+        let mut base = self.rem(modulus);
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = (&result * &base).rem(modulus);
+            }
+            base = (&base * &base).rem(modulus);
+            exponent >>= 1;
+        }
+        result
+    }
+
+    // Computes self^(2^(128 * times)) mod modulus, i.e. applies the Frobenius map.
+    pub fn frobenius_mod(&self, times: usize, modulus: &Gf128Polynomial) -> Gf128Polynomial {
+        let mut result = self.rem(modulus);
+        for _ in 0..128 * times {
+            result = (&result * &result).rem(modulus);
+        }
+        result
+    }
+
+    // Returns the square root of a polynomial which only has even powers.
+    fn sqrt(&self) -> Gf128Polynomial {
+        let coefficients: Vec<Gf128> = self
+            .coefficients
+            .iter()
+            .step_by(2)
+            .map(|c| c.pow(1 << 127))
+            .collect();
+        Gf128Polynomial::new(&coefficients)
+    }
+
+    pub fn square_free_factorization(&self) -> Vec<(Gf128Polynomial, usize)> {
+        let f = self.monic();
+        let mut result = Vec::new();
+
+        let mut c = f.gcd(&f.derivative());
+        let mut w = f.div_rem(&c).0;
+
+        let mut i = 1;
+        while !w.is_one() {
+            let y = w.gcd(&c);
+            let factor = w.div_rem(&y).0;
+            if factor.degree() > Some(0) {
+                result.push((factor, i));
+            }
+            w = y;
+            c = c.div_rem(&w).0;
+            i += 1;
+        }
+
+        if !c.is_one() {
+            for (factor, multiplicity) in c.sqrt().square_free_factorization() {
+                result.push((factor, multiplicity * 2));
+            }
+        }
+
+        result
+    }
+
+    pub fn distinct_degree_factorization(&self) -> Vec<(Gf128Polynomial, usize)> {
+        let mut f = self.monic();
+        let mut result = Vec::new();
+
+        let mut h = Gf128Polynomial::x();
+        let mut i = 1;
+        while f.degree().unwrap_or(0) >= 2 * i {
+            h = h.frobenius_mod(1, &f);
+            let g = f.gcd(&(&h - &Gf128Polynomial::x()));
+            if !g.is_one() {
+                f = f.div_rem(&g).0;
+                h = h.rem(&f);
+                result.push((g, i));
+            }
+            i += 1;
+        }
+
+        if let Some(degree) = f.degree() {
+            if degree > 0 {
+                result.push((f, degree));
+            }
+        }
+
+        result
+    }
+
+    pub fn equal_degree_factorization(&self, degree: usize) -> Vec<Gf128Polynomial> {
+        let f = self.monic();
+        let n = f.degree().unwrap_or(0);
+        let factor_count = n / degree;
+
+        let mut factors = vec![f.clone()];
+        while factors.len() < factor_count {
+            let a = Gf128Polynomial::random(n);
+            let mut trace = a.rem(&f);
+            let mut power = trace.clone();
+            for _ in 1..128 * degree {
+                power = (&power * &power).rem(&f);
+                trace = &trace + &power;
+            }
+
+            let mut new_factors = Vec::new();
+            for u in factors {
+                if u.degree() == Some(degree) {
+                    new_factors.push(u);
+                    continue;
+                }
+                let g = u.gcd(&trace);
+                if g.is_one() || g == u {
+                    new_factors.push(u);
+                } else {
+                    new_factors.push(u.div_rem(&g).0);
+                    new_factors.push(g);
+                }
+            }
+            factors = new_factors;
+        }
+
+        factors
+    }
+
+    pub fn factor(&self) -> Vec<(Gf128Polynomial, usize)> {
+        let mut result = Vec::new();
+        for (square_free, multiplicity) in self.square_free_factorization() {
+            for (equal_degree, degree) in square_free.distinct_degree_factorization() {
+                for factor in equal_degree.equal_degree_factorization(degree) {
+                    result.push((factor, multiplicity));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn roots(&self) -> Vec<Gf128> {
+        let mut result = Vec::new();
+        for (square_free, _) in self.square_free_factorization() {
+            for (equal_degree, degree) in square_free.distinct_degree_factorization() {
+                if degree != 1 {
+                    continue;
+                }
+                for factor in equal_degree.equal_degree_factorization(1) {
+                    result.push(factor.coefficients[0]);
+                }
+            }
+        }
+        result
+    }
+
+    fn normalize(&mut self) {
+        while let Some(c) = self.coefficients.last() {
+            if c.is_zero() {
+                self.coefficients.pop();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl Add for &Gf128Polynomial {
+    type Output = Gf128Polynomial;
+
+    fn add(self, rhs: &Gf128Polynomial) -> Gf128Polynomial {
+        let len = self.coefficients.len().max(rhs.coefficients.len());
+        let coefficients: Vec<Gf128> = (0..len)
+            .map(|i| {
+                *self.coefficients.get(i).unwrap_or(&Gf128::zero())
+                    + *rhs.coefficients.get(i).unwrap_or(&Gf128::zero())
+            })
+            .collect();
+        Gf128Polynomial::new(&coefficients)
+    }
+}
+
+impl Sub for &Gf128Polynomial {
+    type Output = Gf128Polynomial;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: &Gf128Polynomial) -> Gf128Polynomial {
+        self + rhs
+    }
+}
+
+impl Mul for &Gf128Polynomial {
+    type Output = Gf128Polynomial;
+
+    fn mul(self, rhs: &Gf128Polynomial) -> Gf128Polynomial {
+        if self.is_zero() || rhs.is_zero() {
+            return Gf128Polynomial::zero();
+        }
+        let mut coefficients =
+            vec![Gf128::zero(); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                coefficients[i + j] += *a * *b;
+            }
+        }
+        Gf128Polynomial::new(&coefficients)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn linear(root: Gf128) -> Gf128Polynomial {
+        Gf128Polynomial::new(&[root, Gf128::one()])
+    }
+
+    #[test]
+    fn polynomial_division() {
+        let a = Gf128Polynomial::random(7);
+        let b = Gf128Polynomial::random(4);
+
+        let (q, r) = a.div_rem(&b);
+        assert!(r.degree() < b.degree());
+        assert_eq!(&(&q * &b) + &r, a);
+    }
+
+    #[test]
+    fn polynomial_roots() {
+        let roots = [Gf128::random(), Gf128::random(), Gf128::random()];
+        let quadratic = Gf128Polynomial::new(&[Gf128::random(), Gf128::random(), Gf128::one()]);
+
+        let mut f = quadratic.clone();
+        for root in roots {
+            f = &f * &linear(root);
+        }
+        // repeated root
+        f = &f * &linear(roots[0]);
+        f = f.scale(Gf128::random());
+
+        let mut found = f.roots();
+        found.sort_by_key(|r| r.0);
+        let mut expected = roots.to_vec();
+        expected.sort_by_key(|r| r.0);
+        // a random quadratic may split as well
+        found.retain(|r| quadratic.evaluate(*r) != Gf128::zero());
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn polynomial_factorization() {
+        let a = Gf128Polynomial::new(&[Gf128::random(), Gf128::one()]);
+        let b = Gf128Polynomial::new(&[Gf128::random(), Gf128::random(), Gf128::one()]);
+        let f = &(&(&a * &a) * &b) * &b;
+
+        let factors = f.factor();
+        let mut product = Gf128Polynomial::one();
+        for (factor, multiplicity) in &factors {
+            for _ in 0..*multiplicity {
+                product = &product * factor;
+            }
+        }
+        assert_eq!(product, f);
+        assert!(factors.iter().any(|(factor, m)| *factor == a && *m == 2));
+    }
+}
@@ -0,0 +1,122 @@
+use rug::Integer;
+
+pub trait Group {
+    type Element: Clone + PartialEq;
+
+    fn identity(&self) -> Self::Element;
+    fn operate(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+    fn inverse(&self, a: &Self::Element) -> Self::Element;
+
+    // Cheap hash of an element, used to derive pseudo-random walks.
+    fn fingerprint(&self, a: &Self::Element) -> u64;
+
+    fn pow(&self, base: &Self::Element, exponent: &Integer) -> Self::Element {
+        let base = if *exponent < 0 {
+            self.inverse(base)
+        } else {
+            base.clone()
+        };
+        let exponent = exponent.clone().abs();
+
+        let mut result = self.identity();
+        for i in (0..exponent.significant_bits()).rev() {
+            result = self.operate(&result, &result);
+            if exponent.get_bit(i) {
+                result = self.operate(&result, &base);
+            }
+        }
+        result
+    }
+}
+
+// The multiplicative group of integers modulo p.
+#[derive(Clone)]
+pub struct MultiplicativeGroup {
+    p: Integer,
+}
+
+impl MultiplicativeGroup {
+    pub fn new(p: &Integer) -> MultiplicativeGroup {
+        MultiplicativeGroup { p: p.clone() }
+    }
+
+    pub fn modulus(&self) -> &Integer {
+        &self.p
+    }
+}
+
+impl Group for MultiplicativeGroup {
+    type Element = Integer;
+
+    fn identity(&self) -> Integer {
+        Integer::from(1)
+    }
+
+    fn operate(&self, a: &Integer, b: &Integer) -> Integer {
+        Integer::from(a * b) % &self.p
+    }
+
+    fn inverse(&self, a: &Integer) -> Integer {
+        a.clone()
+            .invert(&self.p)
+            .expect("element is not invertible")
+    }
+
+    fn fingerprint(&self, a: &Integer) -> u64 {
+        a.to_u64_wrapping()
+    }
+
+    fn pow(&self, base: &Integer, exponent: &Integer) -> Integer {
+        base.clone()
+            .pow_mod(exponent, &self.p)
+            .expect("element is not invertible")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    struct AdditiveGroup(u64);
+
+    impl Group for AdditiveGroup {
+        type Element = u64;
+
+        fn identity(&self) -> u64 {
+            0
+        }
+
+        fn operate(&self, a: &u64, b: &u64) -> u64 {
+            (a + b) % self.0
+        }
+
+        fn inverse(&self, a: &u64) -> u64 {
+            (self.0 - a) % self.0
+        }
+
+        fn fingerprint(&self, a: &u64) -> u64 {
+            *a
+        }
+    }
+
+    #[test]
+    fn group_pow() {
+        let group = MultiplicativeGroup::new(&Integer::from(101));
+        let base = Integer::from(3);
+        assert_eq!(group.pow(&base, &Integer::from(5)), 243 % 101);
+        assert_eq!(
+            group.operate(
+                &group.pow(&base, &Integer::from(-7)),
+                &group.pow(&base, &Integer::from(7))
+            ),
+            1
+        );
+
+        // the default square and multiply
+        let additive = AdditiveGroup(1000);
+        assert_eq!(additive.pow(&7, &Integer::from(123)), 861);
+        assert_eq!(additive.pow(&7, &Integer::from(-1)), 993);
+        assert_eq!(additive.pow(&7, &Integer::new()), 0);
+    }
+}
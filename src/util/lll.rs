@@ -0,0 +1,125 @@
+use rug::{Integer, Rational};
+
+fn dot(a: &[Integer], b: &[Integer]) -> Integer {
+    a.iter().zip(b).map(|(x, y)| Integer::from(x * y)).sum()
+}
+
+// Gram-Schmidt coefficients mu[i][j] and squared lengths of the orthogonal
+// vectors, computed from inner products only.
+fn gram_schmidt(basis: &[Vec<Integer>]) -> (Vec<Vec<Rational>>, Vec<Rational>) {
+    let n = basis.len();
+    let mut mu = vec![vec![Rational::new(); n]; n];
+    let mut norms: Vec<Rational> = Vec::with_capacity(n);
+    for i in 0..n {
+        for j in 0..i {
+            let mut value = Rational::from(dot(&basis[i], &basis[j]));
+            for k in 0..j {
+                value -= Rational::from(&mu[j][k] * &mu[i][k]) * &norms[k];
+            }
+            mu[i][j] = value / &norms[j];
+        }
+        let mut norm = Rational::from(dot(&basis[i], &basis[i]));
+        for j in 0..i {
+            norm -= Rational::from(mu[i][j].square_ref()) * &norms[j];
+        }
+        norms.push(norm);
+    }
+    (mu, norms)
+}
+
+// Lenstra-Lenstra-Lovasz reduction of linearly independent row vectors with
+// exact rational arithmetic. delta is usually 3/4 or a little closer to 1.
+pub fn lll(basis: &[Vec<Integer>], delta: &Rational) -> Vec<Vec<Integer>> {
+    let mut b = basis.to_vec();
+    let n = b.len();
+    let (mut mu, mut norms) = gram_schmidt(&b);
+    let half = Rational::from((1, 2));
+
+    let mut k = 1;
+    while k < n {
+        // size reduction of b_k
+        for j in (0..k).rev() {
+            if Rational::from(mu[k][j].abs_ref()) > half {
+                let r = Integer::from(mu[k][j].round_ref());
+                let (head, tail) = b.split_at_mut(k);
+                for (x, y) in tail[0].iter_mut().zip(&head[j]) {
+                    *x -= Integer::from(&r * y);
+                }
+                let (head, tail) = mu.split_at_mut(k);
+                for (x, y) in tail[0][..j].iter_mut().zip(&head[j][..j]) {
+                    *x -= Rational::from(y * &r);
+                }
+                mu[k][j] -= &r;
+            }
+        }
+
+        // Lovasz condition
+        let bound = (delta.clone() - Rational::from(mu[k][k - 1].square_ref())) * &norms[k - 1];
+        if norms[k] >= bound {
+            k += 1;
+            continue;
+        }
+
+        // swap b_k and b_(k-1) and update the Gram-Schmidt data in place
+        b.swap(k, k - 1);
+        let m = mu[k][k - 1].clone();
+        let norm = Rational::from(&norms[k]) + Rational::from(m.square_ref()) * &norms[k - 1];
+        mu[k][k - 1] = Rational::from(&m * &norms[k - 1]) / &norm;
+        norms[k] = Rational::from(&norms[k - 1] * &norms[k]) / &norm;
+        norms[k - 1] = norm;
+        let (head, tail) = mu.split_at_mut(k);
+        head[k - 1][..k - 1].swap_with_slice(&mut tail[0][..k - 1]);
+        let new_m = mu[k][k - 1].clone();
+        for row in mu.iter_mut().skip(k + 1) {
+            let t = row[k].clone();
+            row[k] = row[k - 1].clone() - Rational::from(&m * &t);
+            row[k - 1] = t + Rational::from(&new_m * &row[k]);
+        }
+        k = (k - 1).max(1);
+    }
+    b
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn to_basis(rows: &[&[i64]]) -> Vec<Vec<Integer>> {
+        rows.iter()
+            .map(|row| row.iter().map(|x| Integer::from(*x)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn lll_test() {
+        let delta = Rational::from((99, 100));
+
+        // same result as recomputing Gram-Schmidt from scratch after every step
+        let basis = to_basis(&[&[-2, 0, 2, 0], &[1, 2, 0, 0], &[1, 0, 2, 1], &[-1, 0, 2, 2]]);
+        let reduced = lll(&basis, &delta);
+        assert_eq!(
+            reduced,
+            to_basis(&[&[1, 2, 0, 0], &[1, 0, 0, 2], &[0, 0, 2, -1], &[-2, 0, 0, 1]])
+        );
+
+        // the reduced basis satisfies the size and Lovasz conditions
+        let basis = to_basis(&[
+            &[1, 0, 0, 0, 314159],
+            &[0, 1, 0, 0, 271828],
+            &[0, 0, 1, 0, 161803],
+            &[0, 0, 0, 1, 141421],
+        ]);
+        let reduced = lll(&basis, &delta);
+        let (mu, norms) = gram_schmidt(&reduced);
+        for i in 1..reduced.len() {
+            for mu_ij in &mu[i][..i] {
+                assert!(Rational::from(mu_ij.abs_ref()) <= (1, 2));
+            }
+            let bound = (delta.clone() - Rational::from(mu[i][i - 1].square_ref())) * &norms[i - 1];
+            assert!(norms[i] >= bound);
+        }
+        let shortest = reduced.iter().map(|row| dot(row, row)).min().unwrap();
+        assert!(shortest < dot(&basis[0], &basis[0]));
+    }
+}
@@ -1,7 +1,15 @@
 pub mod algebra;
 pub mod base_64;
 pub mod bits;
+pub mod block_cipher;
+pub mod deflate;
+pub mod error;
 pub mod generators;
+pub mod gf128;
+pub mod gf128_polynomial;
+pub mod group;
 pub mod integer;
+pub mod lll;
 pub mod progress_bar;
 pub mod xor;
+pub mod xtea;
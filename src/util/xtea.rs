@@ -0,0 +1,101 @@
+use super::{
+    bits::{u32_to_big_endian, u8_vector_to_u32},
+    block_cipher::BlockCipher,
+    error::{CryptoError, Result},
+};
+
+const DELTA: u32 = 0x9E3779B9;
+const CYCLES: u32 = 32;
+
+pub struct Xtea {
+    key: [u32; 4],
+}
+
+impl Xtea {
+    pub fn new(key: &[u8]) -> Result<Xtea> {
+        if key.len() != 16 {
+            return Err(CryptoError::InvalidKeyLength(key.len()));
+        }
+        let mut key_words = [0; 4];
+        for (word, chunk) in key_words.iter_mut().zip(key.chunks_exact(4)) {
+            *word = u8_vector_to_u32(chunk);
+        }
+        Ok(Xtea { key: key_words })
+    }
+}
+
+impl BlockCipher for Xtea {
+    fn block_size(&self) -> usize {
+        8
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        let mut v0 = u8_vector_to_u32(&block[0..4]);
+        let mut v1 = u8_vector_to_u32(&block[4..8]);
+        let mut sum: u32 = 0;
+
+        for _ in 0..CYCLES {
+            v0 = v0.wrapping_add(
+                ((v1 << 4) ^ (v1 >> 5)).wrapping_add(v1)
+                    ^ sum.wrapping_add(self.key[(sum & 3) as usize]),
+            );
+            sum = sum.wrapping_add(DELTA);
+            v1 = v1.wrapping_add(
+                ((v0 << 4) ^ (v0 >> 5)).wrapping_add(v0)
+                    ^ sum.wrapping_add(self.key[((sum >> 11) & 3) as usize]),
+            );
+        }
+
+        block[0..4].copy_from_slice(&u32_to_big_endian(v0));
+        block[4..8].copy_from_slice(&u32_to_big_endian(v1));
+    }
+
+    fn decrypt_block(&self, block: &mut [u8]) {
+        let mut v0 = u8_vector_to_u32(&block[0..4]);
+        let mut v1 = u8_vector_to_u32(&block[4..8]);
+        let mut sum: u32 = DELTA.wrapping_mul(CYCLES);
+
+        for _ in 0..CYCLES {
+            v1 = v1.wrapping_sub(
+                ((v0 << 4) ^ (v0 >> 5)).wrapping_add(v0)
+                    ^ sum.wrapping_add(self.key[((sum >> 11) & 3) as usize]),
+            );
+            sum = sum.wrapping_sub(DELTA);
+            v0 = v0.wrapping_sub(
+                ((v1 << 4) ^ (v1 >> 5)).wrapping_add(v1)
+                    ^ sum.wrapping_add(self.key[(sum & 3) as usize]),
+            );
+        }
+
+        block[0..4].copy_from_slice(&u32_to_big_endian(v0));
+        block[4..8].copy_from_slice(&u32_to_big_endian(v1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn xtea_kat() {
+        let xtea = Xtea::new(&hex::decode("000102030405060708090a0b0c0d0e0f").unwrap()).unwrap();
+        let mut block = b"ABCDEFGH".to_vec();
+        xtea.encrypt_block(&mut block);
+        assert_eq!(hex::encode(&block), "497df3d072612cb5");
+        xtea.decrypt_block(&mut block);
+        assert_eq!(block, b"ABCDEFGH");
+
+        let xtea = Xtea::new(&[0; 16]).unwrap();
+        let mut block = b"ABCDEFGH".to_vec();
+        xtea.encrypt_block(&mut block);
+        assert_eq!(hex::encode(&block), "a0390589f8b8efa5");
+        xtea.decrypt_block(&mut block);
+        assert_eq!(block, b"ABCDEFGH");
+
+        assert_eq!(
+            Xtea::new(&[0; 8]).err(),
+            Some(CryptoError::InvalidKeyLength(8))
+        );
+    }
+}